@@ -0,0 +1,72 @@
+//! Benchmarks of every generator and solver on the real inputs, one group per day.
+//!
+//! `cargo bench -- day6/` runs a single day, which makes comparing variants easy. Days of
+//! years other than 2022 are named like `2023/day6`.
+//!
+//! With `--features count-allocations`, the allocations of every generator and solver are
+//! printed before it is benchmarked.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use aoc_2022::{
+    allocations::measure,
+    registry,
+    runner::{default_input_path, read_input},
+    DEFAULT_YEAR,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// Solvers slower than this get the minimum number of samples.
+const SLOW: Duration = Duration::from_millis(50);
+
+fn bench_solutions(c: &mut Criterion) {
+    for solution in registry().iter() {
+        let (year, day) = (solution.year(), solution.day());
+        let input = match read_input(&default_input_path(year, day)) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("skipping {year} day {day}: {e:#}");
+                continue;
+            }
+        };
+        let (data, allocations) = measure(|| solution.parse(&input));
+        let data = data.expect("generator failed");
+
+        let name = match year {
+            DEFAULT_YEAR => format!("day{day}"),
+            year => format!("{year}/day{day}"),
+        };
+        if let Some(stats) = allocations {
+            println!("{name}/generator: {stats}");
+        }
+        let mut group = c.benchmark_group(&name);
+        group.bench_function("generator", |b| {
+            b.iter(|| solution.parse(black_box(&input)))
+        });
+
+        for variant in solution.variants() {
+            let id = match variant.name {
+                Some(name) => format!("part{}/{name}", variant.part),
+                None => format!("part{}", variant.part),
+            };
+
+            let start_time = Instant::now();
+            let (answer, allocations) = measure(|| solution.solve(&data, variant));
+            answer.expect("solver failed");
+            let slow = start_time.elapsed() > SLOW;
+            if let Some(stats) = allocations {
+                println!("{name}/{id}: {stats}");
+            }
+            group.sample_size(if slow { 10 } else { 100 });
+
+            group.bench_function(id, |b| b.iter(|| solution.solve(black_box(&data), variant)));
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_solutions);
+criterion_main!(benches);
@@ -0,0 +1,173 @@
+//! Counting the allocations of generators and solvers, with the `count-allocations` feature.
+//!
+//! The counters are kept per thread, so parts solved in parallel don't mix. Without the
+//! feature the system allocator is used as is, and nothing is measured.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt::Display,
+};
+
+/// The allocations made while running a generator or solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AllocStats {
+    /// Allocations, counting a reallocation as one.
+    pub count: u64,
+    /// Bytes allocated, counting only the growth of reallocations.
+    pub bytes: u64,
+    /// The most memory live at once, above what was live at the start.
+    pub peak: u64,
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.count,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+/// Bytes in the largest unit that keeps the number at least 1, like `3.4 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+struct Counters {
+    count: Cell<u64>,
+    bytes: Cell<u64>,
+    /// Can go below zero when memory from another thread is freed here.
+    live: Cell<i64>,
+    peak: Cell<i64>,
+}
+
+thread_local! {
+    static COUNTERS: Counters = const {
+        Counters {
+            count: Cell::new(0),
+            bytes: Cell::new(0),
+            live: Cell::new(0),
+            peak: Cell::new(0),
+        }
+    };
+}
+
+fn record(count: u64, bytes: u64, live_change: i64) {
+    // Fails only while the thread is going away, when nobody is measuring anymore.
+    let _ = COUNTERS.try_with(|c| {
+        c.count.set(c.count.get() + count);
+        c.bytes.set(c.bytes.get() + bytes);
+        let live = c.live.get() + live_change;
+        c.live.set(live);
+        c.peak.set(c.peak.get().max(live));
+    });
+}
+
+/// The system allocator, counting what it does for [`measure`].
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(1, layout.size() as u64, layout.size() as i64);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(1, layout.size() as u64, layout.size() as i64);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, 0, -(layout.size() as i64));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            let growth = new_size.saturating_sub(layout.size()) as u64;
+            record(1, growth, new_size as i64 - layout.size() as i64);
+        }
+        new_ptr
+    }
+}
+
+/// Run `f` and count its allocations on this thread, if the `count-allocations` feature is
+/// on. Measurements can be nested.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    if !cfg!(feature = "count-allocations") {
+        return (f(), None);
+    }
+    let snapshot = || COUNTERS.with(|c| (c.count.get(), c.bytes.get(), c.live.get()));
+    let (count, bytes, live) = snapshot();
+    let outer_peak = COUNTERS.with(|c| c.peak.replace(live));
+
+    let value = f();
+
+    let (end_count, end_bytes, _) = snapshot();
+    let peak = COUNTERS.with(|c| c.peak.replace(c.peak.get().max(outer_peak)));
+    let stats = AllocStats {
+        count: end_count - count,
+        bytes: end_bytes - bytes,
+        peak: (peak - live).max(0) as u64,
+    };
+    (value, Some(stats))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_bytes, measure};
+
+    #[test]
+    #[cfg_attr(
+        not(feature = "count-allocations"),
+        ignore = "needs the counting allocator (use feature 'count-allocations' to enable)"
+    )]
+    fn allocations_are_counted() {
+        let (_, stats) = measure(|| {
+            let mut v = Vec::<u8>::with_capacity(1000);
+            drop(Vec::<u8>::with_capacity(4000));
+            v.push(1);
+            v
+        });
+        let stats = stats.expect("counted with the feature on");
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.bytes, 5000);
+        assert_eq!(stats.peak, 5000);
+
+        // The outer measurement sees the inner one's peak.
+        let (_, outer) = measure(|| {
+            let (_, inner) = measure(|| drop(vec![0u8; 300]));
+            assert_eq!(inner.map(|s| s.peak), Some(300));
+            vec![0u8; 100]
+        });
+        assert_eq!(outer.map(|s| (s.count, s.peak)), Some((2, 300)));
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 << 30), "5.0 GiB");
+    }
+}
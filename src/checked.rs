@@ -0,0 +1,131 @@
+//! Arithmetic for the steps of solvers that can overflow on unexpected input.
+//!
+//! Every operation is checked, in debug and release builds alike, and an overflow is an
+//! error naming the day, the step and the values.
+
+use std::{any::type_name, fmt::Display};
+
+use anyhow::{anyhow, Result};
+
+/// The integer types that solvers do arithmetic with.
+pub trait Integer: Copy + Display {
+    const ZERO: Self;
+    const ONE: Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),+) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+            }
+        )+
+    };
+}
+
+impl_integer!(i32, i64, usize);
+
+/// The arithmetic of one day, e.g. `Checked::day(7).sub("free space", total, used)?`.
+#[derive(Debug, Clone, Copy)]
+pub struct Checked {
+    day: u32,
+}
+
+impl Checked {
+    pub const fn day(day: u32) -> Self {
+        Self { day }
+    }
+
+    pub fn add<T: Integer>(self, step: &str, a: T, b: T) -> Result<T> {
+        self.apply(step, a, '+', b, T::checked_add)
+    }
+
+    pub fn sub<T: Integer>(self, step: &str, a: T, b: T) -> Result<T> {
+        self.apply(step, a, '-', b, T::checked_sub)
+    }
+
+    pub fn mul<T: Integer>(self, step: &str, a: T, b: T) -> Result<T> {
+        self.apply(step, a, '*', b, T::checked_mul)
+    }
+
+    /// The sum of `values`, every addition checked as `step`.
+    pub fn sum<T: Integer>(self, step: &str, values: impl IntoIterator<Item = T>) -> Result<T> {
+        values
+            .into_iter()
+            .try_fold(T::ZERO, |total, value| self.add(step, total, value))
+    }
+
+    /// The product of `values`, every multiplication checked as `step`.
+    pub fn product<T: Integer>(self, step: &str, values: impl IntoIterator<Item = T>) -> Result<T> {
+        values
+            .into_iter()
+            .try_fold(T::ONE, |total, value| self.mul(step, total, value))
+    }
+
+    fn apply<T: Integer>(
+        self,
+        step: &str,
+        a: T,
+        op: char,
+        b: T,
+        checked: fn(T, T) -> Option<T>,
+    ) -> Result<T> {
+        checked(a, b).ok_or_else(|| {
+            anyhow!(
+                "day {}, {step}: {a} {op} {b} doesn't fit in {}",
+                self.day,
+                type_name::<T>()
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Checked;
+    use anyhow::Result;
+
+    const CHECKED: Checked = Checked::day(0);
+
+    #[test]
+    fn in_range() -> Result<()> {
+        assert_eq!(CHECKED.add("a", 2, 3)?, 5);
+        assert_eq!(CHECKED.sub("b", 2usize, 2)?, 0);
+        assert_eq!(CHECKED.mul("c", -4i64, 5)?, -20);
+        assert_eq!(CHECKED.sum("d", [1, 2, 3])?, 6);
+        assert_eq!(CHECKED.product("e", Vec::<usize>::new())?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        let error = Checked::day(7)
+            .sub("space to free", 3usize, 5)
+            .expect_err("should overflow");
+        assert_eq!(
+            error.to_string(),
+            "day 7, space to free: 3 - 5 doesn't fit in usize"
+        );
+
+        let error = CHECKED
+            .sum("total", [i32::MAX, 1])
+            .expect_err("should overflow");
+        assert_eq!(
+            error.to_string(),
+            "day 0, total: 2147483647 + 1 doesn't fit in i32"
+        );
+    }
+}
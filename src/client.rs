@@ -0,0 +1,380 @@
+use std::{
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_CONFIG_PATH: &str = ".aoc.toml";
+pub const DEFAULT_STATE_DIR: &str = ".aoc";
+pub const DEFAULT_USER_AGENT: &str = "github.com/2Flwrs/aoc-2022 (aoc-2022 runner)";
+
+/// Settings for talking to the Advent of Code site.
+///
+/// Read from `.aoc.toml` (or the file named by `AOC_CONFIG`), where `AOC_SESSION` and
+/// `AOC_BASE_URL` from the environment take precedence:
+///
+/// ```toml
+/// session = "53616c7465645f5f..."
+/// base_url = "https://adventofcode.com"
+/// user_agent = "github.com/me/aoc-2022 by me@example.com"
+/// min_interval_secs = 5
+/// state_dir = ".aoc"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: String,
+    pub user_agent: String,
+    /// The shortest time between two requests, across processes.
+    pub min_interval: Duration,
+    /// Where local bookkeeping (like the time of the last request) is kept.
+    pub state_dir: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            session: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            min_interval: Duration::from_secs(5),
+            state_dir: PathBuf::from(DEFAULT_STATE_DIR),
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        let path = std::env::var_os("AOC_CONFIG")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH));
+
+        let mut config = if path.exists() {
+            Self::load_file(&path)?
+        } else {
+            Self::default()
+        };
+
+        if let Ok(session) = std::env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = std::env::var("AOC_BASE_URL") {
+            config.base_url = base_url;
+        }
+        Ok(config)
+    }
+
+    pub fn load_file(path: &Path) -> Result<Self> {
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("could not read {path:?}"))?;
+        Self::from_toml(&text).with_context(|| format!("bad config file {path:?}"))
+    }
+
+    fn from_toml(text: &str) -> Result<Self> {
+        let table: toml::Table = text.parse()?;
+
+        let mut config = Self::default();
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("session", toml::Value::String(s)) => config.session = Some(s),
+                ("base_url", toml::Value::String(s)) => config.base_url = s,
+                ("user_agent", toml::Value::String(s)) => config.user_agent = s,
+                ("min_interval_secs", toml::Value::Integer(n)) => {
+                    let secs = u64::try_from(n).context("min_interval_secs is negative")?;
+                    config.min_interval = Duration::from_secs(secs)
+                }
+                ("state_dir", toml::Value::String(s)) => config.state_dir = PathBuf::from(s),
+                (key, value) => Err(anyhow!("unexpected {} {key:?}", value.type_str()))?,
+            }
+        }
+        Ok(config)
+    }
+
+    fn session(&self) -> Result<&str> {
+        self.session.as_deref().ok_or_else(|| {
+            anyhow!("no session token (set AOC_SESSION or `session` in {DEFAULT_CONFIG_PATH})")
+        })
+    }
+}
+
+/// A blocking client for the Advent of Code site.
+pub struct Client {
+    config: Config,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(config: Config) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(&config.user_agent)
+            .timeout(Duration::from_secs(30))
+            .build();
+        Self { config, agent }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.config.base_url.trim_end_matches('/'))
+    }
+
+    fn last_request_file(&self) -> PathBuf {
+        self.config.state_dir.join("last-request")
+    }
+
+    /// Sleep until `min_interval` has passed since the last request, then record this one.
+    fn wait_for_turn(&self) -> Result<()> {
+        let file = self.last_request_file();
+        let last = std::fs::read_to_string(&file)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .map(|nanos| UNIX_EPOCH + Duration::from_nanos(nanos));
+
+        if let Some(last) = last {
+            let since = SystemTime::now().duration_since(last).unwrap_or_default();
+            if since < self.config.min_interval {
+                thread::sleep(self.config.min_interval - since);
+            }
+        }
+
+        std::fs::create_dir_all(&self.config.state_dir)
+            .with_context(|| format!("could not create {:?}", self.config.state_dir))?;
+        // Nanoseconds, as rounding down to coarser units would end the next wait early.
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+        std::fs::write(&file, now.to_string())
+            .with_context(|| format!("could not write {file:?}"))?;
+        Ok(())
+    }
+
+    fn get(&self, path: &str) -> Result<String> {
+        let request = self
+            .agent
+            .get(&self.url(path))
+            .set("Cookie", &format!("session={}", self.config.session()?));
+        self.wait_for_turn()?;
+        read_response(path, request.call())
+    }
+
+    pub(crate) fn post_form(&self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        let request = self
+            .agent
+            .post(&self.url(path))
+            .set("Cookie", &format!("session={}", self.config.session()?));
+        self.wait_for_turn()?;
+        read_response(path, request.send_form(form))
+    }
+
+    /// Download the puzzle input for `day` of `year`.
+    pub fn fetch_input(&self, year: u32, day: u32) -> Result<String> {
+        self.get(&format!("/{year}/day/{day}/input"))
+    }
+
+    /// Download the input for `day` of `year` into `dir`, unless it is already there.
+    ///
+    /// Returns the path of the input file and whether it was downloaded.
+    pub fn ensure_input(&self, year: u32, day: u32, dir: &Path) -> Result<(PathBuf, bool)> {
+        let path = dir.join(format!("day{day}.txt"));
+        if path.exists() {
+            return Ok((path, false));
+        }
+
+        let input = self.fetch_input(year, day)?;
+        std::fs::create_dir_all(dir).with_context(|| format!("could not create {dir:?}"))?;
+        // Write to a temporary name first, so an interrupted download doesn't look cached.
+        let partial = path.with_extension("txt.partial");
+        std::fs::write(&partial, input).with_context(|| format!("could not write {partial:?}"))?;
+        std::fs::rename(&partial, &path).with_context(|| format!("could not write {path:?}"))?;
+        Ok((path, true))
+    }
+}
+
+fn read_response(path: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String> {
+    match response {
+        Ok(response) => Ok(response.into_string()?),
+        Err(ureq::Error::Status(code, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            let reason = match code {
+                400 | 401 => "the session token was rejected",
+                404 => "not found (is the puzzle unlocked yet?)",
+                _ => "unexpected response",
+            };
+            Err(anyhow!("{path}: {reason} (HTTP {code}): {}", body.trim()))
+        }
+        Err(e) => Err(anyhow!("{path}: request failed: {e}")),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::{Client, Config};
+    use anyhow::Result;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+        time::Duration,
+    };
+
+    /// A request as seen by [`serve`].
+    #[derive(Debug)]
+    pub(crate) struct SeenRequest {
+        pub request_line: String,
+        pub headers: Vec<String>,
+        pub body: String,
+    }
+
+    impl SeenRequest {
+        pub(crate) fn header(&self, name: &str) -> Option<&str> {
+            self.headers.iter().find_map(|h| {
+                let (key, value) = h.split_once(':')?;
+                key.eq_ignore_ascii_case(name).then(|| value.trim())
+            })
+        }
+    }
+
+    /// Answer the next `responses.len()` requests on a local port with the given
+    /// status codes and bodies. Returns the base URL and the recorded requests.
+    pub(crate) fn serve(
+        responses: Vec<(u16, &'static str)>,
+    ) -> (String, JoinHandle<Vec<SeenRequest>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut seen = vec![];
+            for (status, response) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut headers = vec![];
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end().to_string();
+                    if line.is_empty() {
+                        break;
+                    }
+                    headers.push(line);
+                }
+                let mut request = SeenRequest {
+                    request_line: request_line.trim_end().to_string(),
+                    headers,
+                    body: String::new(),
+                };
+                let length = request
+                    .header("content-length")
+                    .map_or(0, |l| l.parse::<usize>().unwrap());
+                let mut bytes = vec![0; length];
+                reader.read_exact(&mut bytes).unwrap();
+                request.body = String::from_utf8(bytes).unwrap();
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                    response.len()
+                )
+                .unwrap();
+                seen.push(request);
+            }
+            seen
+        });
+
+        (base_url, handle)
+    }
+
+    pub(crate) fn test_config(base_url: String, state_dir: &std::path::Path) -> Config {
+        Config {
+            session: Some("secret".to_string()),
+            base_url,
+            min_interval: Duration::ZERO,
+            state_dir: state_dir.to_path_buf(),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn fetch_sends_session_and_user_agent() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (base_url, server) = serve(vec![(200, "1\n2\n")]);
+        let client = Client::new(test_config(base_url, dir.path()));
+
+        assert_eq!(client.fetch_input(2022, 3)?, "1\n2\n");
+
+        let seen = server.join().unwrap();
+        assert_eq!(seen[0].request_line, "GET /2022/day/3/input HTTP/1.1");
+        assert_eq!(seen[0].header("cookie"), Some("session=secret"));
+        assert_eq!(
+            seen[0].header("user-agent"),
+            Some(super::DEFAULT_USER_AGENT)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_input_only_downloads_missing_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (base_url, server) = serve(vec![(200, "abc\n")]);
+        let client = Client::new(test_config(base_url, dir.path()));
+
+        let (path, fetched) = client.ensure_input(2023, 6, dir.path())?;
+        assert!(fetched);
+        assert_eq!(std::fs::read_to_string(&path)?, "abc\n");
+
+        // The server only answers once, so a second download would fail.
+        let (_, fetched) = client.ensure_input(2023, 6, dir.path())?;
+        assert!(!fetched);
+        let seen = server.join().unwrap();
+        assert_eq!(seen[0].request_line, "GET /2023/day/6/input HTTP/1.1");
+        Ok(())
+    }
+
+    #[test]
+    fn reports_rejected_session() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (base_url, server) = serve(vec![(400, "Please log in")]);
+        let client = Client::new(test_config(base_url, dir.path()));
+
+        let err = client.fetch_input(2022, 1).unwrap_err();
+        assert!(
+            err.to_string().contains("session token was rejected"),
+            "{err}"
+        );
+        assert!(!dir.path().join("day1.txt").exists());
+        server.join().unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn waits_between_requests() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (base_url, server) = serve(vec![(200, "a"), (200, "b")]);
+        let mut config = test_config(base_url, dir.path());
+        config.min_interval = Duration::from_millis(300);
+        let client = Client::new(config);
+
+        let start = std::time::Instant::now();
+        client.fetch_input(2022, 1)?;
+        client.fetch_input(2022, 2)?;
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        server.join().unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn config_file() -> Result<()> {
+        let config = Config::from_toml("session = \"abc\"\nmin_interval_secs = 10\n")?;
+        assert_eq!(config.session.as_deref(), Some("abc"));
+        assert_eq!(config.min_interval, Duration::from_secs(10));
+        assert_eq!(config.base_url, super::DEFAULT_BASE_URL);
+        assert!(Config::from_toml("sesion = \"abc\"").is_err());
+        Ok(())
+    }
+}
@@ -1,52 +1,113 @@
-use anyhow::Result;
-use aoc_runner_derive::{aoc, aoc_generator};
+use std::str::FromStr;
 
+use anyhow::{anyhow, Result};
+use tracing::debug;
+
+use crate::{
+    checked::Checked,
+    normalize::Normalize,
+    parse::{number, parse_records, trimmed, ParseError},
+    solution::{Day, Part, Solution},
+    stream::Stream,
+};
+
+const CHECKED: Checked = Checked::day(1);
+
+/// The calories carried by one elf, a number per line.
 struct Data(Vec<usize>);
 
-#[aoc_generator(day1)]
-fn parse_data(input: &str) -> Result<Vec<Data>> {
-    let mut data = vec![];
-    let mut set = vec![];
-    for line in input.lines() {
-        if line.trim().is_empty() {
-            if !set.is_empty() {
-                data.push(Data(set));
-                set = vec![];
-            }
-        } else {
-            let value: usize = line.trim().parse()?;
-            set.push(value)
-        }
-    }
-    if !set.is_empty() {
-        data.push(Data(set));
+impl FromStr for Data {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let set = s
+            .lines()
+            .enumerate()
+            .map(|(index, line)| number(line, trimmed(line)).map_err(|e| e.offset_lines(index)))
+            .collect::<Result<_, _>>()?;
+        Ok(Data(set))
     }
+}
+
+pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(1, parse_data)
+            .normalize(Normalize::TRIM)
+            .part1(|d| part1(d))
+            .part2(|d| part2(d))
+            .stream(Part::One, part1_stream)
+            .stream(Part::Two, part2_stream),
+    )
+}
+
+fn parse_data(input: &str) -> Result<Vec<Data>, ParseError> {
+    let data = parse_records::<Data>(input)?;
+    debug!(elves = data.len(), "parsed");
     Ok(data)
 }
 
-#[aoc(day1, part1)]
+fn totals(data: &[Data]) -> impl Iterator<Item = Result<usize>> + '_ {
+    data.iter()
+        .map(|Data(set)| CHECKED.sum("calories of an elf", set.iter().copied()))
+}
+
+/// Add `total` to the `n` largest totals so far, kept largest first.
+fn keep_largest(largest: &mut Vec<usize>, n: usize, total: usize) {
+    let at = largest.partition_point(|&t| t >= total);
+    if at < n {
+        largest.insert(at, total);
+        largest.truncate(n);
+    }
+}
+
+fn largest(totals: impl Iterator<Item = Result<usize>>, n: usize) -> Result<Vec<usize>> {
+    let mut largest = Vec::with_capacity(n + 1);
+    for total in totals {
+        keep_largest(&mut largest, n, total?);
+    }
+    Ok(largest)
+}
+
 fn part1(data: &[Data]) -> Result<usize> {
-    let max = data
-        .iter()
-        .map(|Data(set)| set.iter().sum::<usize>())
-        .max()
-        .ok_or_else(|| anyhow::anyhow!("no max?"))?;
+    let max = largest(totals(data), 1)?
+        .first()
+        .copied()
+        .ok_or_else(|| anyhow!("no max?"))?;
 
     Ok(max)
 }
 
-#[aoc(day1, part2)]
 fn part2(data: &[Data]) -> Result<usize> {
-    let mut totals = data
-        .iter()
-        .map(|Data(set)| set.iter().sum::<usize>())
-        .collect::<Vec<_>>();
+    CHECKED.sum("top three calories", largest(totals(data), 3)?)
+}
 
-    totals.sort_unstable_by(|a, b| a.cmp(b).reverse());
+/// Call `f` with the total of every elf, as the input is read.
+fn stream_totals(stream: Stream<'_>, mut f: impl FnMut(usize)) -> Result<()> {
+    let mut total = None;
+    stream.for_each_line(|index, line| {
+        if line.trim().is_empty() {
+            total.take().into_iter().for_each(&mut f);
+        } else {
+            let value = number(line, trimmed(line)).map_err(|e| e.offset_lines(index))?;
+            let total = total.get_or_insert(0);
+            *total = CHECKED.add("calories of an elf", *total, value)?;
+        }
+        Ok(())
+    })?;
+    total.into_iter().for_each(f);
+    Ok(())
+}
 
-    let sum3 = totals.iter().take(3).sum::<usize>();
+fn part1_stream(stream: Stream<'_>) -> Result<usize> {
+    let mut largest = vec![];
+    stream_totals(stream, |total| keep_largest(&mut largest, 1, total))?;
+    largest.first().copied().ok_or_else(|| anyhow!("no max?"))
+}
 
-    Ok(sum3)
+fn part2_stream(stream: Stream<'_>) -> Result<usize> {
+    let mut largest = vec![];
+    stream_totals(stream, |total| keep_largest(&mut largest, 3, total))?;
+    CHECKED.sum("top three calories", largest)
 }
 
 #[cfg(test)]
@@ -67,4 +128,15 @@ mod test {
         assert_eq!(part2(&parse_data(EXAMPLE_INPUT)?)?, 45000);
         Ok(())
     }
+
+    #[test]
+    fn top_three_overflow() -> Result<()> {
+        let error =
+            part2(&parse_data("18446744073709551615\n\n1\n")?).expect_err("should overflow");
+        assert_eq!(
+            error.to_string(),
+            "day 1, top three calories: 18446744073709551615 + 1 doesn't fit in usize"
+        );
+        Ok(())
+    }
 }
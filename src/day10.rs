@@ -1,26 +1,35 @@
 use std::str::FromStr;
 
-use anyhow::{Error, Result};
-use aoc_runner_derive::{aoc, aoc_generator};
+use anyhow::Result;
 use bitvec::vec::BitVec;
 use itertools::Itertools;
-use parse_display::{Display, FromStr};
+use parse_display::Display;
+use tracing::{debug, trace};
+
+use crate::{
+    checked::Checked,
+    normalize::Normalize,
+    parse::{ParseError, Words},
+    solution::{Answer, Day, Part, Solution},
+    stream::Stream,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Program(Vec<Instruction>);
 
 impl FromStr for Program {
-    type Err = Error;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Program(
             s.lines()
-                .map(|line| line.parse())
+                .enumerate()
+                .map(|(index, line)| line.parse().map_err(|e: ParseError| e.offset_lines(index)))
                 .collect::<Result<Vec<Instruction>, _>>()?,
         ))
     }
 }
 
-#[derive(Display, FromStr, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Display, Debug, PartialEq, Eq, Clone, Copy)]
 enum Instruction {
     #[display("noop")]
     NoOp,
@@ -28,6 +37,21 @@ enum Instruction {
     AddX(i32),
 }
 
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = Words::new(s);
+        let instruction = match words.next("`noop` or `addx`")? {
+            "noop" => Instruction::NoOp,
+            "addx" => Instruction::AddX(words.number()?),
+            _ => Err(ParseError::at(s, words.last_span(), "`noop` or `addx`"))?,
+        };
+        words.end()?;
+        Ok(instruction)
+    }
+}
+
 impl Instruction {
     fn dt_dx(&self) -> (i32, i32) {
         match *self {
@@ -37,41 +61,73 @@ impl Instruction {
     }
 }
 
-#[aoc_generator(day10)]
-fn parse_data(input: &str) -> Result<Program> {
-    Ok(input.parse()?)
+pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(10, parse_data)
+            .normalize(Normalize::TRIM)
+            .part1(part1)
+            .part2(part2)
+            .stream(Part::One, part1_stream)
+            .stream(Part::Two, part2_stream),
+    )
 }
 
+fn parse_data(input: &str) -> Result<Program, ParseError> {
+    let program: Program = input.parse()?;
+    debug!(instructions = program.0.len(), "parsed");
+    Ok(program)
+}
+
+const CHECKED: Checked = Checked::day(10);
+
 const TARGET_CYCLES: [i32; 6] = [20, 60, 100, 140, 180, 220];
 
-#[aoc(day10, part1)]
-fn part1(pgm: &Program) -> Result<i32> {
-    let mut x = 1;
-    let mut cycle = 1;
-    let mut targets = TARGET_CYCLES.iter().cloned();
+/// The sum of the signal strengths at the [`TARGET_CYCLES`], as instructions execute.
+struct Signal {
+    x: i32,
+    cycle: i32,
+    targets: std::slice::Iter<'static, i32>,
+    current_target: Option<i32>,
+    value: i32,
+}
 
-    let mut value = 0;
+impl Signal {
+    fn new() -> Self {
+        let mut targets = TARGET_CYCLES.iter();
+        let current_target = targets.next().copied();
+        Self {
+            x: 1,
+            cycle: 1,
+            targets,
+            current_target,
+            value: 0,
+        }
+    }
 
-    let mut current_target = targets.next();
-    for ins in pgm.0.iter().cloned() {
+    fn run_instruction(&mut self, ins: Instruction) -> Result<()> {
         let (dt, dx) = ins.dt_dx();
-        let new_cycle = cycle + dt;
-
-        if current_target.map_or(false, |targ| new_cycle > targ) {
-            let targ = current_target.unwrap();
-            let strength = x * targ;
-            // println!(
-            //     "Before applying {ins}, advancing to {new_cycle}, add {x} * {targ} = {strength}"
-            // );
-            value += strength;
-            current_target = targets.next();
+        let new_cycle = CHECKED.add("cycle + dt", self.cycle, dt)?;
+
+        if let Some(targ) = self.current_target.filter(|&targ| new_cycle > targ) {
+            let strength = CHECKED.mul("signal strength x * cycle", self.x, targ)?;
+            debug!(cycle = targ, x = self.x, strength, "signal");
+            self.value = CHECKED.add("sum of signal strengths", self.value, strength)?;
+            self.current_target = self.targets.next().copied();
         }
 
-        cycle = new_cycle;
-        x += dx;
+        self.cycle = new_cycle;
+        self.x = CHECKED.add("x + dx", self.x, dx)?;
+        trace!(%ins, cycle = self.cycle, x = self.x, "executed");
+        Ok(())
     }
+}
 
-    Ok(value)
+fn part1(pgm: &Program) -> Result<i32> {
+    let mut signal = Signal::new();
+    for ins in pgm.0.iter().cloned() {
+        signal.run_instruction(ins)?;
+    }
+    Ok(signal.value)
 }
 
 #[derive(PartialEq, Eq, Clone)]
@@ -122,6 +178,15 @@ impl Screen {
     }
 }
 
+impl From<Screen> for Answer {
+    fn from(screen: Screen) -> Self {
+        Answer::Bitmap {
+            width: screen.width,
+            pixels: screen.data.iter().map(|x| *x).collect(),
+        }
+    }
+}
+
 impl std::fmt::Display for Screen {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Screen:")?;
@@ -153,15 +218,17 @@ impl Cpu {
         }
     }
 
-    fn run_instruction(&mut self, instruction: Instruction) {
+    fn run_instruction(&mut self, instruction: Instruction) -> Result<()> {
+        trace!(%instruction, x = self.x, screen_pos = self.screen_pos, "executing");
         match instruction {
             Instruction::NoOp => self.do_cycle(),
             Instruction::AddX(dx) => {
                 self.do_cycle();
                 self.do_cycle();
-                self.x += dx;
+                self.x = CHECKED.add("x + dx", self.x, dx)?;
             }
         }
+        Ok(())
     }
 
     fn get_screen(&self) -> Screen {
@@ -169,27 +236,48 @@ impl Cpu {
     }
 
     fn do_cycle(&mut self) {
-        let bit = (self.x - self.screen_pos).abs() <= 1;
+        let bit = self.x.abs_diff(self.screen_pos) <= 1;
         self.screen.push(bit);
 
         self.screen_pos += 1;
-        self.screen_pos = self.screen_pos % (self.screen.width as i32);
+        self.screen_pos %= self.screen.width as i32;
     }
 }
 
-#[aoc(day10, part2)]
 fn part2(data: &Program) -> Result<Screen> {
     let mut cpu = Cpu::new(40);
     for ins in data.0.iter().cloned() {
-        cpu.run_instruction(ins);
+        cpu.run_instruction(ins)?;
     }
     Ok(cpu.get_screen())
 }
 
+/// Execute the instructions as they are read, parsed like [`Program`] does.
+fn stream_program(stream: Stream<'_>, mut f: impl FnMut(Instruction) -> Result<()>) -> Result<()> {
+    stream.for_each_line(|index, line| {
+        f(line
+            .parse()
+            .map_err(|e: ParseError| e.offset_lines(index))?)
+    })
+}
+
+fn part1_stream(stream: Stream<'_>) -> Result<i32> {
+    let mut signal = Signal::new();
+    stream_program(stream, |ins| signal.run_instruction(ins))?;
+    Ok(signal.value)
+}
+
+fn part2_stream(stream: Stream<'_>) -> Result<Screen> {
+    let mut cpu = Cpu::new(40);
+    stream_program(stream, |ins| cpu.run_instruction(ins))?;
+    Ok(cpu.get_screen())
+}
+
 #[cfg(test)]
 mod test {
-    use super::{parse_data, part1, part2};
+    use super::{parse_data, part1, part2, Instruction};
     use anyhow::Result;
+    use proptest::prelude::*;
 
     const EXAMPLE_INPUT: &str = include_str!("../input/2022/day10.example.txt");
 
@@ -199,6 +287,24 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn x_overflows() -> Result<()> {
+        let program = parse_data("addx 2147483646\naddx 1\n")?;
+        let error = part2(&program).expect_err("should overflow");
+        assert_eq!(
+            error.to_string(),
+            "day 10, x + dx: 2147483647 + 1 doesn't fit in i32"
+        );
+
+        let program = parse_data(&format!("addx 200000000\n{}", "noop\n".repeat(20)))?;
+        let error = part1(&program).expect_err("should overflow");
+        assert_eq!(
+            error.to_string(),
+            "day 10, signal strength x * cycle: 200000001 * 20 doesn't fit in i32"
+        );
+        Ok(())
+    }
+
     #[test]
     fn part2_example() -> Result<()> {
         let result = part2(&parse_data(EXAMPLE_INPUT)?)?;
@@ -217,4 +323,18 @@ mod test {
         assert_eq!(result, expected);
         Ok(())
     }
+
+    fn instruction() -> impl Strategy<Value = Instruction> {
+        prop_oneof![
+            Just(Instruction::NoOp),
+            any::<i32>().prop_map(Instruction::AddX),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn instruction_round_trip(instruction in instruction()) {
+            prop_assert_eq!(instruction.to_string().parse::<Instruction>()?, instruction);
+        }
+    }
 }
@@ -1,12 +1,21 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::BTreeMap, fmt::Display, ops::Range, str::FromStr};
 
-use anyhow::{anyhow, Result};
-use aoc_runner_derive::{aoc, aoc_generator};
+use anyhow::{anyhow, Context, Result};
 use evalexpr::{build_operator_tree, Node};
 use itertools::Itertools;
-use parse_display::{Display, FromStr};
+use tracing::{debug, enabled, trace, Level};
 
-#[derive(Display, FromStr, Debug, Clone, PartialEq, Eq)]
+use crate::{
+    checked::Checked,
+    normalize::Normalize,
+    parse::{number, parse_records, trimmed, ParseError, Words},
+    solution::{Day, Solution},
+};
+
+/// Worry levels don't need it: `evalexpr` already reports overflow as an error.
+const CHECKED: Checked = Checked::day(11);
+
+#[derive(parse_display::Display, Debug, Clone, PartialEq, Eq)]
 #[display(
     "{index}\n  {starting_items}\n  {op}\n  {test_div}\n    {target_true}\n    {target_false}"
 )]
@@ -28,45 +37,157 @@ struct MonkeyInfo {
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct ItemList(Vec<i64>);
 
-impl std::str::FromStr for ItemList {
-    type Err = anyhow::Error;
+impl MonkeyInfo {
+    /// Parse the next line of a monkey's block with `parse`, which has to use up the line.
+    fn parse_line<'a, T>(
+        lines: &mut impl Iterator<Item = (usize, &'a str)>,
+        end: usize,
+        expected: &str,
+        parse: impl FnOnce(&mut Words<'a>) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let (index, line) = lines
+            .next()
+            .ok_or_else(|| ParseError::new("", 1, expected, "end of input").offset_lines(end))?;
+        let mut words = Words::new(line);
+        let value = parse(&mut words).map_err(|e| e.offset_lines(index))?;
+        words.end().map_err(|e| e.offset_lines(index))?;
+        Ok(value)
+    }
+
+    /// The divisor of the test, which has to be above 0 to be of any use.
+    fn divisor(&self) -> Result<i64> {
+        if self.test_div <= 0 {
+            Err(anyhow!(
+                "monkey {} can't test if worry levels are divisible by {}",
+                self.index,
+                self.test_div
+            ))?;
+        }
+        Ok(self.test_div)
+    }
+}
+
+impl FromStr for MonkeyInfo {
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let list = s
-            .split(",")
-            .map(|x| x.trim().parse::<i64>())
+        let mut lines = s.lines().enumerate();
+        let end = s.lines().count();
+
+        let index = Self::parse_line(&mut lines, end, "`Monkey`", |w| {
+            w.keyword("Monkey")?;
+            let expected = "a monkey number like `0:`";
+            let word = w.next(expected)?;
+            word.strip_suffix(':')
+                .and_then(|n| n.parse().ok())
+                .ok_or_else(|| ParseError::at(w.line(), w.last_span(), expected))
+        })?;
+        let starting_items = Self::parse_line(&mut lines, end, "`Starting items:`", |w| {
+            w.keyword("Starting")?;
+            w.keyword("items:")?;
+            w.rest_or_empty();
+            ItemList::parse(w.line(), w.last_span())
+        })?;
+        let op = Self::parse_line(&mut lines, end, "`Operation:`", |w| {
+            w.keyword("Operation:")?;
+            w.keyword("new")?;
+            w.keyword("=")?;
+            let op = w.rest("an expression")?;
+            build_operator_tree(op)
+                .map_err(|_| ParseError::at(w.line(), w.last_span(), "an expression in `old`"))?;
+            Ok(op.to_string())
+        })?;
+        let test_div = Self::parse_line(&mut lines, end, "`Test:`", |w| {
+            w.keyword("Test:")?;
+            w.keyword("divisible")?;
+            w.keyword("by")?;
+            w.number()
+        })?;
+        let mut target = |outcome: &str| {
+            Self::parse_line(&mut lines, end, &format!("`If {outcome}`"), |w| {
+                w.keyword("If")?;
+                w.keyword(outcome)?;
+                w.keyword("throw")?;
+                w.keyword("to")?;
+                w.keyword("monkey")?;
+                w.number()
+            })
+        };
+        let target_true = target("true:")?;
+        let target_false = target("false:")?;
+
+        if let Some((index, line)) = lines.next() {
+            Err(ParseError::at(line, trimmed(line), "an empty line").offset_lines(index))?;
+        }
+
+        Ok(Self {
+            index,
+            starting_items,
+            op,
+            test_div,
+            target_true,
+            target_false,
+        })
+    }
+}
+
+impl ItemList {
+    /// Parse the bytes `items` of `line`, like `79, 98`. A monkey may have no items.
+    fn parse(line: &str, items: Range<usize>) -> Result<Self, ParseError> {
+        if line[items.clone()].trim().is_empty() {
+            return Ok(Self(vec![]));
+        }
+        let mut start = items.start;
+        let list = line[items]
+            .split(',')
+            .map(|x| {
+                let item = trimmed(x);
+                let span = start + item.start..start + item.end;
+                start += x.len() + 1;
+                number(line, span)
+            })
             .collect::<Result<Vec<i64>, _>>()?;
         Ok(Self(list))
     }
 }
 
+impl FromStr for ItemList {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, 0..s.len())
+    }
+}
+
 impl std::fmt::Display for ItemList {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.iter().map(|x| format!("{x}")).join(", ").fmt(f)
     }
 }
 
-#[aoc_generator(day11)]
-fn parse_data(input: &str) -> Result<Vec<MonkeyInfo>> {
-    let mut monkeys = vec![];
-    let mut lines = vec![];
-
-    for line in input.lines() {
-        if line.trim().is_empty() {
-            let monkey = lines.join("\n").trim().parse()?;
-            monkeys.push(monkey);
-            lines.clear();
-        } else {
-            lines.push(line);
-        }
-    }
+pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(11, parse_data)
+            .normalize(Normalize::TRIM)
+            .part1(|d| part1(d))
+            .part2(|d| part2(d))
+            .cross_check(
+                "relief off",
+                &[
+                    ("Monkey", |d| relief_off_with_worry(d)),
+                    ("Monkey2", |d| relief_off_with_residues(d)),
+                ],
+            ),
+    )
+}
 
-    let last = lines.join("\n");
-    if !last.trim().is_empty() {
-        let monkey = lines.join("\n").trim().parse()?;
-        monkeys.push(monkey);
+fn parse_data(input: &str) -> Result<Vec<MonkeyInfo>, ParseError> {
+    let monkeys = parse_records::<MonkeyInfo>(input)?;
+    if monkeys.len() < 2 {
+        Err(ParseError::new("", 1, "at least 2 monkeys", "end of input")
+            .offset_lines(input.lines().count()))?;
     }
-
+    debug!(monkeys = monkeys.len(), "parsed");
     Ok(monkeys)
 }
 
@@ -86,7 +207,7 @@ impl Monkey {
     fn from_info(info: &MonkeyInfo) -> Result<Self> {
         let items = info.starting_items.0.clone();
         let op = build_operator_tree(&info.op)?;
-        let test_div = info.test_div;
+        let test_div = info.divisor()?;
         let target_true = info.target_true;
         let target_false = info.target_false;
         Ok(Self {
@@ -99,7 +220,8 @@ impl Monkey {
         })
     }
 
-    fn process_items(&mut self) -> Result<Vec<(usize, i64)>> {
+    /// Inspect and throw every item, dividing worry levels by 3 if `relief` is on.
+    fn process_items(&mut self, relief: bool) -> Result<Vec<(usize, i64)>> {
         let mut thrown = vec![];
 
         for item in self.items.iter().cloned() {
@@ -111,7 +233,7 @@ impl Monkey {
 
             let new_item = self.op.eval_int_with_context(&ctx)?;
 
-            let new_item = new_item / 3;
+            let new_item = if relief { new_item / 3 } else { new_item };
 
             let target = if new_item % self.test_div == 0 {
                 self.target_true
@@ -135,57 +257,60 @@ impl Monkey {
     }
 }
 
-#[aoc(day11, part1)]
 fn part1(info: &[MonkeyInfo]) -> Result<usize> {
+    monkey_business(info, 20, true)
+}
+
+fn monkey_business(info: &[MonkeyInfo], rounds: usize, relief: bool) -> Result<usize> {
     let mut monkeys = info
         .iter()
         .map(Monkey::from_info)
         .collect::<Result<Vec<_>, _>>()?;
 
-    for _round in 0..20 {
+    for round in 1..=rounds {
         for monkey_num in 0..monkeys.len() {
-            let thrown = monkeys[monkey_num].process_items()?;
+            let thrown = monkeys[monkey_num]
+                .process_items(relief)
+                .with_context(|| format!("monkey {monkey_num}"))?;
             for (target, item) in thrown {
                 monkeys
                     .get_mut(target)
-                    .ok_or_else(|| anyhow!("bad index {target}"))?
+                    .ok_or_else(|| {
+                        anyhow!("monkey {monkey_num} throws to monkey {target}, which isn't there")
+                    })?
                     .fetch_item(item);
             }
         }
 
-        #[cfg(feature = "verbose")]
-        {
-            eprintln!("Round {}:", round + 1);
-            for (num, m) in monkeys.iter().enumerate() {
-                let list = m.items.iter().map(|i| format!("{i}")).join(", ");
-                eprintln!("Monkey {num}: {list}");
+        if enabled!(Level::DEBUG) {
+            for (monkey, m) in monkeys.iter().enumerate() {
+                debug!(round, monkey, items = %m.items.iter().join(", "), "round done");
             }
-            eprintln!();
         }
-
-        // dbg!(&monkeys);
     }
 
-    Ok(monkeys
-        .iter()
-        .map(Monkey::inspected_total)
-        .sorted()
-        .rev()
-        .take(2)
-        .product())
+    CHECKED.product(
+        "monkey business",
+        monkeys
+            .iter()
+            .map(Monkey::inspected_total)
+            .sorted()
+            .rev()
+            .take(2),
+    )
 }
 
 // ============================================================================
 
+/// The remainder of the worry level for each divisor, in order so errors are reproducible.
 #[derive(Debug)]
-struct Item(HashMap<i64, i64>);
+struct Item(BTreeMap<i64, i64>);
 
 impl Display for Item {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let inner = self
             .0
             .iter()
-            .sorted_by_key(|(k, _v)| **k)
             .map(|(div, val)| format!("{val}%{div}"))
             .join(", ");
         write!(f, "[{inner}]")
@@ -197,13 +322,14 @@ impl Item {
         Self(divs.iter().map(|&d| (d, initial.rem_euclid(d))).collect())
     }
 
-    fn is_div_by(&self, div: i64) -> bool {
-        self.0.get(&div).map(|x| *x % div == 0).unwrap()
+    fn is_div_by(&self, div: i64) -> Result<bool> {
+        self.0
+            .get(&div)
+            .map(|x| *x % div == 0)
+            .ok_or_else(|| anyhow!("no remainder of {div} was kept"))
     }
 
     fn operate(&mut self, op: &Node) -> Result<()> {
-        // println!("## Operating on {self}");
-        // println!(" # with {op}");
         for (&div, val) in &mut self.0 {
             let old = *val;
             let ctx = evalexpr::context_map! {
@@ -212,12 +338,8 @@ impl Item {
             let new = op.eval_int_with_context(&ctx)?;
             let rem = new.rem_euclid(div);
             *val = rem;
-
-            // println!(" # old={old}  new={new}  == {rem} mod {div}");
+            trace!(%op, old, new, div, rem, "operated");
         }
-        // println!(" # Results in {self}");
-        // println!();
-
         Ok(())
     }
 }
@@ -241,7 +363,7 @@ impl Monkey2 {
             .map(|&initial| Item::from_divs(initial, all_divs))
             .collect();
         let op = build_operator_tree(&info.op)?;
-        let test_div = info.test_div;
+        let test_div = info.divisor()?;
         let target_true = info.target_true;
         let target_false = info.target_false;
         Ok(Self {
@@ -262,7 +384,7 @@ impl Monkey2 {
 
             item.operate(&self.op)?;
 
-            let target = if item.is_div_by(self.test_div) {
+            let target = if item.is_div_by(self.test_div)? {
                 self.target_true
             } else {
                 self.target_false
@@ -284,75 +406,91 @@ impl Monkey2 {
     }
 }
 
-#[aoc(day11, part2)]
 fn part2(info: &[MonkeyInfo]) -> Result<usize> {
-    let all_divs = info.iter().map(|m| m.test_div).collect::<Vec<_>>();
+    monkey_business_residues(info, 10000)
+}
+
+/// Like [`monkey_business`] without relief, keeping only the remainders of the worry levels.
+fn monkey_business_residues(info: &[MonkeyInfo], rounds: usize) -> Result<usize> {
+    let all_divs = info
+        .iter()
+        .map(MonkeyInfo::divisor)
+        .collect::<Result<Vec<_>>>()?;
 
     let mut monkeys = info
         .iter()
         .map(|i| Monkey2::from_info(i, &all_divs))
         .collect::<Result<Vec<_>, _>>()?;
 
-    // println!("== At start ==");
-    // print_monkeys2(&monkeys);
-    // println!();
-
-    // let debug_list = [1, 20, 10000];
-    for _round in 0..10000 {
+    for round in 1..=rounds {
         for monkey_num in 0..monkeys.len() {
-            let thrown = monkeys[monkey_num].process_items()?;
+            let thrown = monkeys[monkey_num]
+                .process_items()
+                .with_context(|| format!("monkey {monkey_num}"))?;
             for (target, item) in thrown {
                 monkeys
                     .get_mut(target)
-                    .ok_or_else(|| anyhow!("bad index {target}"))?
+                    .ok_or_else(|| {
+                        anyhow!("monkey {monkey_num} throws to monkey {target}, which isn't there")
+                    })?
                     .catch_item(item);
             }
         }
 
-        #[cfg(feature = "verbose")]
-        {
-            eprintln!("Round {}:", round + 1);
-            for (num, m) in monkeys.iter().enumerate() {
-                let list = m.items.iter().map(|i| format!("{i}")).join(", ");
-                eprintln!("Monkey {num}: {list}");
+        if enabled!(Level::DEBUG) {
+            for (monkey, m) in monkeys.iter().enumerate() {
+                debug!(
+                    round,
+                    monkey,
+                    inspected = m.inspected_total,
+                    items = %m.items.iter().join("; "),
+                    "round done"
+                );
             }
-            eprintln!();
         }
+    }
 
-        // let round_number = _round + 1;
-        // if debug_list.contains(&round_number) {
-        //     println!("== After round {round_number} ==");
-        //     print_monkeys2(&monkeys);
-        //     println!();
-        // }
+    CHECKED.product(
+        "monkey business",
+        monkeys
+            .iter()
+            .map(Monkey2::inspected_total)
+            .sorted()
+            .rev()
+            .take(2),
+    )
+}
 
-        // dbg!(&monkeys);
-    }
+// ============================================================================
 
-    Ok(monkeys
-        .iter()
-        .map(Monkey2::inspected_total)
-        .sorted()
-        .rev()
-        .take(2)
-        .product())
+/// Both ways of keeping track of worry levels must agree while they fit in an `i64`. `None`
+/// when they don't.
+fn relief_off_with_worry(info: &[MonkeyInfo]) -> Result<Option<usize>> {
+    use evalexpr::EvalexprError::*;
+    match monkey_business(info, 20, false) {
+        Err(e) => match e.downcast_ref() {
+            Some(AdditionError { .. } | SubtractionError { .. } | MultiplicationError { .. }) => {
+                Ok(None)
+            }
+            _ => Err(e),
+        },
+        result => result.map(Some),
+    }
 }
 
-// fn print_monkeys2(monkeys: &[Monkey2]) {
-//     for (i, m) in monkeys.iter().enumerate() {
-//         println!("Monkey {i} inspected items {} times", m.inspected_total);
-//         for item in &m.items {
-//             println!("  item {item}",);
-//         }
-//     }
-// }
+/// Remainders never overflow, so this always has an answer or an error.
+fn relief_off_with_residues(info: &[MonkeyInfo]) -> Result<Option<usize>> {
+    monkey_business_residues(info, 20).map(Some)
+}
 
 // ============================================================================
 
 #[cfg(test)]
 mod test {
-    use super::{parse_data, part1, part2};
+    use super::{parse_data, part1, part2, ItemList, MonkeyInfo};
     use anyhow::Result;
+    use itertools::Itertools;
+    use proptest::prelude::*;
 
     const EXAMPLE_INPUT: &str = include_str!("../input/2022/day11.example.txt");
 
@@ -377,4 +515,82 @@ mod test {
         assert_eq!(part2(&parse_data(EXAMPLE_INPUT)?)?, 2713310158);
         Ok(())
     }
+
+    #[test]
+    fn parse_error_location() {
+        let input = EXAMPLE_INPUT.replace("Starting items: 74", "Starting items: 7x4");
+        let error = parse_data(&input).expect_err("should not parse");
+        assert_eq!((error.line, error.column), (23, 19));
+        assert_eq!(error.text, "  Starting items: 7x4");
+    }
+
+    #[test]
+    fn needs_two_monkeys() {
+        let error = parse_data("").expect_err("should not parse");
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (1, "at least 2 monkeys")
+        );
+
+        let first = EXAMPLE_INPUT.split("\n\n").next().unwrap_or_default();
+        let error = parse_data(first).expect_err("should not parse");
+        assert_eq!((error.line, error.column), (7, 1));
+    }
+
+    #[test]
+    fn example_round_trip() -> Result<()> {
+        let monkeys = parse_data(EXAMPLE_INPUT)?;
+        assert_eq!(monkeys.iter().join("\n\n") + "\n", EXAMPLE_INPUT);
+        Ok(())
+    }
+
+    fn item_list() -> impl Strategy<Value = ItemList> {
+        prop::collection::vec(any::<i64>(), 0..6).prop_map(ItemList)
+    }
+
+    fn operation() -> impl Strategy<Value = String> {
+        let operand = prop_oneof![
+            Just("old".to_string()),
+            (0..100i64).prop_map(|n| n.to_string())
+        ];
+        (prop_oneof![Just('*'), Just('+')], operand).prop_map(|(op, rhs)| format!("old {op} {rhs}"))
+    }
+
+    fn monkey_info() -> impl Strategy<Value = MonkeyInfo> {
+        (
+            any::<usize>(),
+            item_list(),
+            operation(),
+            any::<i64>(),
+            any::<usize>(),
+            any::<usize>(),
+        )
+            .prop_map(
+                |(index, starting_items, op, test_div, target_true, target_false)| MonkeyInfo {
+                    index,
+                    starting_items,
+                    op,
+                    test_div,
+                    target_true,
+                    target_false,
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn item_list_round_trip(items in item_list()) {
+            prop_assert_eq!(items.to_string().parse::<ItemList>()?, items);
+        }
+
+        #[test]
+        fn monkey_info_round_trip(monkey in monkey_info()) {
+            prop_assert_eq!(monkey.to_string().parse::<MonkeyInfo>()?, monkey);
+        }
+
+        #[test]
+        fn input_round_trip(monkeys in prop::collection::vec(monkey_info(), 2..5)) {
+            prop_assert_eq!(parse_data(&monkeys.iter().join("\n\n"))?, monkeys);
+        }
+    }
 }
@@ -1,29 +1,44 @@
 use anyhow::Result;
-use aoc_runner_derive::{aoc, aoc_generator};
 use parse_display::{Display, FromStr};
 use std::{cmp::Ordering, str::FromStr};
+use tracing::debug;
+
+use crate::{
+    normalize::Normalize,
+    parse::{trimmed, ParseError},
+    solution::{Day, Solution},
+};
+
+pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(2, parse_data)
+            .normalize(Normalize::TRIM)
+            .part1(|d| part1(d))
+            .part2(|d| part2(d)),
+    )
+}
 
-#[aoc_generator(day2)]
-fn parse_data(input: &str) -> Result<Vec<Data>> {
+fn parse_data(input: &str) -> Result<Vec<Data>, ParseError> {
     let mut data = vec![];
-    for line in input.lines() {
+    for (index, line) in input.lines().enumerate() {
         if line.trim().is_empty() {
             continue;
         }
 
-        let row = Data::from_str(line.trim())?;
+        let row = Data::from_str(line.trim()).map_err(|_| {
+            ParseError::at(line, trimmed(line), "a round like `A Y`").offset_lines(index)
+        })?;
         data.push(row);
     }
+    debug!(rounds = data.len(), "parsed");
     Ok(data)
 }
 
-#[aoc(day2, part1)]
 fn part1(data: &[Data]) -> Result<usize> {
     let score = data.iter().map(Data::calc1).sum::<usize>();
     Ok(score)
 }
 
-#[aoc(day2, part2)]
 fn part2(data: &[Data]) -> Result<usize> {
     let score = data.iter().map(Data::calc2).sum::<usize>();
     Ok(score)
@@ -42,7 +57,7 @@ impl Data {
         let played = self.right.as_rps();
 
         let type_score = played.type_score();
-        let result_score = result_score(played.partial_cmp(&opponent).unwrap());
+        let result_score = result_score(played.against(&opponent));
 
         type_score + result_score
     }
@@ -72,7 +87,7 @@ fn result_score(ordering: Ordering) -> usize {
     }
 }
 
-#[derive(Display, FromStr, PartialEq, Debug)]
+#[derive(Display, FromStr, PartialEq, Debug, Clone, Copy)]
 enum Left {
     A,
     B,
@@ -89,7 +104,7 @@ impl Left {
     }
 }
 
-#[derive(Display, FromStr, PartialEq, Debug)]
+#[derive(Display, FromStr, PartialEq, Debug, Clone, Copy)]
 enum Right {
     X,
     Y,
@@ -146,9 +161,11 @@ impl Rps {
     }
 }
 
-impl PartialOrd for Rps {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(match self {
+impl Rps {
+    /// Whether playing this wins (`Greater`) against `other`. Not an [`Ord`], since what
+    /// beats what goes round in a circle.
+    fn against(&self, other: &Rps) -> Ordering {
+        match self {
             Rps::R => match other {
                 Rps::R => Ordering::Equal,
                 Rps::P => Ordering::Less,
@@ -164,14 +181,15 @@ impl PartialOrd for Rps {
                 Rps::P => Ordering::Greater,
                 Rps::S => Ordering::Equal,
             },
-        })
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{parse_data, part1, part2};
+    use super::{parse_data, part1, part2, Data, Left, Right};
     use anyhow::Result;
+    use proptest::prelude::*;
 
     const EXAMPLE_INPUT: &str = include_str!("../input/2022/day2.example.txt");
 
@@ -186,4 +204,17 @@ mod test {
         assert_eq!(part2(&parse_data(EXAMPLE_INPUT)?)?, 12);
         Ok(())
     }
+
+    fn data() -> impl Strategy<Value = Data> {
+        let left = prop_oneof![Just(Left::A), Just(Left::B), Just(Left::C)];
+        let right = prop_oneof![Just(Right::X), Just(Right::Y), Just(Right::Z)];
+        (left, right).prop_map(|(left, right)| Data { left, right })
+    }
+
+    proptest! {
+        #[test]
+        fn data_round_trip(data in data()) {
+            prop_assert_eq!(data.to_string().parse::<Data>()?, data);
+        }
+    }
 }
@@ -1,28 +1,47 @@
-use anyhow::{anyhow, Result};
-use aoc_runner_derive::{aoc, aoc_generator};
+use anyhow::{anyhow, Context, Result};
 use itertools::Itertools;
 use std::collections::HashSet;
+use tracing::debug;
+
+use crate::{
+    normalize::Normalize,
+    parse::{trimmed, ParseError},
+    solution::{Day, Solution},
+};
+
+pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(3, parse_data)
+            .normalize(Normalize::TRIM)
+            .part1(|d| part1(d))
+            .part2(|d| part2(d)),
+    )
+}
 
-#[aoc_generator(day3)]
-fn parse_data(input: &str) -> Result<Vec<Data>> {
+fn parse_data(input: &str) -> Result<Vec<Data>, ParseError> {
     let mut data = vec![];
-    for line in input.lines() {
+    for (index, line) in input.lines().enumerate() {
         if line.trim().is_empty() {
             continue;
         }
 
-        let entry = Data::from_line(&line)?;
+        let entry = Data::from_line(line).map_err(|e| e.offset_lines(index))?;
         data.push(entry);
     }
+    debug!(rucksacks = data.len(), "parsed");
     Ok(data)
 }
 
-#[aoc(day3, part1)]
 fn part1(data: &[Data]) -> Result<usize> {
     let value = data
         .iter()
-        .map(split_data)
-        .map_ok(|(l, r)| matching(&l, &r) as usize)
+        .enumerate()
+        .map(|(index, rucksack)| {
+            split_data(rucksack)
+                .and_then(|(l, r)| matching(&l, &r))
+                .with_context(|| format!("rucksack {}", index + 1))
+        })
+        .map_ok(|item| item as usize)
         .sum::<Result<usize, _>>()?;
 
     Ok(value)
@@ -31,8 +50,8 @@ fn part1(data: &[Data]) -> Result<usize> {
 fn split_data(data: &Data) -> Result<(Vec<u8>, Vec<u8>)> {
     let data = &data.0;
     let len = data.len();
-    if len % 2 != 0 {
-        Err(anyhow!("not an even nnumber of chars"))?;
+    if !len.is_multiple_of(2) {
+        Err(anyhow!("{len} items don't split into two compartments"))?;
     }
 
     let left = data[0..(len / 2)].to_vec();
@@ -41,25 +60,31 @@ fn split_data(data: &Data) -> Result<(Vec<u8>, Vec<u8>)> {
     Ok((left, right))
 }
 
-fn matching(l: &[u8], r: &[u8]) -> u8 {
-    let x = l
-        .iter()
-        .flat_map(|lch| if r.contains(lch) { Some(*lch) } else { None });
-
-    let y = x.collect::<Vec<_>>();
-    assert!(y.iter().all_equal());
-    assert!(y.len() > 0);
-    y[0]
+fn matching(l: &[u8], r: &[u8]) -> Result<u8> {
+    l.iter()
+        .filter(|lch| r.contains(lch))
+        .unique()
+        .copied()
+        .exactly_one()
+        .map_err(|mut found| match found.next() {
+            None => anyhow!("no item is in both compartments"),
+            Some(_) => anyhow!("more than one item is in both compartments"),
+        })
 }
 
-#[aoc(day3, part2)]
 fn part2(data: &[Data]) -> Result<usize> {
     let chunks = data.chunks_exact(3);
     if !chunks.remainder().is_empty() {
-        return Err(anyhow!("not divided into even chunks"));
+        return Err(anyhow!(
+            "can't split {} rucksacks into groups of 3",
+            data.len()
+        ));
     }
 
-    let total = chunks.map(calc_common).sum::<Result<usize, _>>()?;
+    let total = chunks
+        .enumerate()
+        .map(|(index, chunk)| calc_common(chunk).with_context(|| format!("group {}", index + 1)))
+        .sum::<Result<usize, _>>()?;
 
     Ok(total)
 }
@@ -79,7 +104,10 @@ fn calc_common(chunk: &[Data]) -> Result<usize> {
     let answer = overlapping
         .into_iter()
         .exactly_one()
-        .map_err(|_| anyhow!("more than one overlap"))?;
+        .map_err(|mut found| match found.next() {
+            None => anyhow!("no item is in all three rucksacks"),
+            Some(_) => anyhow!("more than one item is in all three rucksacks"),
+        })?;
 
     Ok(answer as usize)
 }
@@ -88,17 +116,24 @@ fn calc_common(chunk: &[Data]) -> Result<usize> {
 struct Data(Vec<u8>);
 
 impl Data {
-    pub(crate) fn from_line(line: &str) -> Result<Self> {
-        let all = line.chars().map(prio).collect::<Result<Vec<_>, _>>()?;
+    pub(crate) fn from_line(line: &str) -> Result<Self, ParseError> {
+        let items = trimmed(line);
+        let all = line[items.clone()]
+            .char_indices()
+            .map(|(i, c)| {
+                let item = items.start + i..items.start + i + c.len_utf8();
+                prio(c).ok_or_else(|| ParseError::at(line, item, "an item `a`-`z` or `A`-`Z`"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(Self(all))
     }
 }
 
-fn prio(c: char) -> Result<u8> {
+fn prio(c: char) -> Option<u8> {
     match c {
-        'a'..='z' => Ok((c as u8 - 'a' as u8) + 1),
-        'A'..='Z' => Ok((c as u8 - 'A' as u8) + 27),
-        _ => Err(anyhow!("invalid character")),
+        'a'..='z' => Some((c as u8 - b'a') + 1),
+        'A'..='Z' => Some((c as u8 - b'A') + 27),
+        _ => None,
     }
 }
 
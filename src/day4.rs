@@ -1,20 +1,39 @@
+use std::{ops::Range, str::FromStr};
+
 use anyhow::Result;
-use aoc_runner_derive::{aoc, aoc_generator};
-use parse_display::{Display, FromStr};
+use parse_display::Display;
+use tracing::debug;
+
+use crate::{
+    normalize::Normalize,
+    parse::{number, trimmed, ParseError},
+    solution::{Day, Solution},
+};
 
-#[aoc_generator(day4)]
-fn parse_data(input: &str) -> Result<Vec<Data>> {
+pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(4, parse_data)
+            .normalize(Normalize::TRIM)
+            .part1(|d| part1(d))
+            .part2(|d| part2(d)),
+    )
+}
+
+fn parse_data(input: &str) -> Result<Vec<Data>, ParseError> {
     let mut data: Vec<Data> = vec![];
-    for line in input.lines() {
+    for (index, line) in input.lines().enumerate() {
         if line.trim().is_empty() {
             continue;
         }
-        data.push(line.parse()?);
+        data.push(
+            line.parse()
+                .map_err(|e: ParseError| e.offset_lines(index))?,
+        );
     }
+    debug!(pairs = data.len(), "parsed");
     Ok(data)
 }
 
-#[aoc(day4, part1)]
 fn part1(data: &[Data]) -> Result<usize> {
     let count = data
         .iter()
@@ -24,7 +43,6 @@ fn part1(data: &[Data]) -> Result<usize> {
     Ok(count)
 }
 
-#[aoc(day4, part2)]
 fn part2(data: &[Data]) -> Result<usize> {
     let count = data
         .iter()
@@ -34,11 +52,25 @@ fn part2(data: &[Data]) -> Result<usize> {
     Ok(count)
 }
 
-#[derive(Display, FromStr, Clone, Copy)]
+#[derive(Display, Clone, Copy, PartialEq, Eq)]
 #[display("{0},{1}")]
 struct Data(ElfRange, ElfRange);
 
-impl Data {}
+impl FromStr for Data {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = trimmed(s);
+        let comma = s[line.clone()]
+            .find(',')
+            .ok_or_else(|| ParseError::at(s, line.end..line.end, "`,` and a second range"))?;
+        let comma = line.start + comma;
+        Ok(Data(
+            ElfRange::parse(s, line.start..comma)?,
+            ElfRange::parse(s, comma + 1..line.end)?,
+        ))
+    }
+}
 
 impl std::fmt::Debug for Data {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -47,13 +79,25 @@ impl std::fmt::Debug for Data {
     }
 }
 
-#[derive(Debug, Display, FromStr, Clone, Copy)]
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
 #[display("{low}-{high}")]
 struct ElfRange {
     low: usize,
     high: usize,
 }
 impl ElfRange {
+    /// Parse the bytes `range` of `line`, like `2-4`.
+    fn parse(line: &str, range: Range<usize>) -> Result<Self, ParseError> {
+        let dash = line[range.clone()]
+            .find('-')
+            .ok_or_else(|| ParseError::at(line, range.clone(), "a range like `2-4`"))?;
+        let dash = range.start + dash;
+        Ok(ElfRange {
+            low: number(line, range.start..dash)?,
+            high: number(line, dash + 1..range.end)?,
+        })
+    }
+
     fn contains(&self, other: &ElfRange) -> bool {
         self.low <= other.low && self.high >= other.high
     }
@@ -67,8 +111,9 @@ impl ElfRange {
 
 #[cfg(test)]
 mod test {
-    use super::{parse_data, part1, part2};
+    use super::{parse_data, part1, part2, Data, ElfRange};
     use anyhow::Result;
+    use proptest::prelude::*;
 
     const EXAMPLE_INPUT: &str = include_str!("../input/2022/day4.example.txt");
 
@@ -83,4 +128,30 @@ mod test {
         assert_eq!(part2(&parse_data(EXAMPLE_INPUT)?)?, 4);
         Ok(())
     }
+
+    #[test]
+    fn parse_error_location() {
+        let error = parse_data("2-4,6-8\n2-3,4x5\n").expect_err("should not parse");
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.text, "2-3,4x5");
+        assert_eq!(error.expected, "a range like `2-4`");
+    }
+
+    fn elf_range() -> impl Strategy<Value = ElfRange> {
+        (any::<usize>(), any::<usize>()).prop_map(|(low, high)| ElfRange { low, high })
+    }
+
+    proptest! {
+        #[test]
+        fn data_round_trip(a in elf_range(), b in elf_range()) {
+            let data = Data(a, b);
+            prop_assert_eq!(data.to_string().parse::<Data>()?, data);
+        }
+
+        #[test]
+        fn elf_range_round_trip(range in elf_range()) {
+            let text = range.to_string();
+            prop_assert_eq!(ElfRange::parse(&text, 0..text.len())?, range);
+        }
+    }
 }
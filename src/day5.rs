@@ -1,16 +1,23 @@
 use std::str::FromStr;
 
-use anyhow::{anyhow, Result};
-use aoc_runner_derive::{aoc, aoc_generator};
+use anyhow::{anyhow, Context, Result};
 use itertools::Itertools;
 use parse_display::{Display, FromStr};
+use tracing::{debug, trace};
 
+use crate::{
+    normalize::Normalize,
+    parse::{trimmed, ParseError, Words},
+    solution::{Day, Solution},
+};
+
+#[derive(Debug, PartialEq, Eq)]
 struct Data {
     stacks: CrateStack,
     instructions: Vec<Instruction>,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct CrateStack(Vec<Vec<char>>);
 
 impl CrateStack {
@@ -19,14 +26,16 @@ impl CrateStack {
             let from = self
                 .0
                 .get_mut(instruction.from)
-                .ok_or_else(|| anyhow!("from index too large: {}", instruction.from))?;
+                .ok_or_else(|| anyhow!("there is no stack {}", instruction.from + 1))?;
 
-            let Some(e) = from.pop() else {Err(anyhow!("selected stack is empty"))?};
+            let Some(e) = from.pop() else {
+                Err(anyhow!("stack {} is empty", instruction.from + 1))?
+            };
 
             let to = self
                 .0
                 .get_mut(instruction.to)
-                .ok_or_else(|| anyhow!("to index too large: {}", instruction.to))?;
+                .ok_or_else(|| anyhow!("there is no stack {}", instruction.to + 1))?;
 
             to.push(e);
         }
@@ -38,18 +47,20 @@ impl CrateStack {
         let from = self
             .0
             .get_mut(instruction.from)
-            .ok_or_else(|| anyhow!("from index too large: {}", instruction.from))?;
+            .ok_or_else(|| anyhow!("there is no stack {}", instruction.from + 1))?;
 
         let mut tmp = vec![];
         for _ in 0..instruction.count {
-            let Some(e) = from.pop() else {Err(anyhow!("selected stack is empty"))?};
+            let Some(e) = from.pop() else {
+                Err(anyhow!("stack {} is empty", instruction.from + 1))?
+            };
             tmp.push(e);
         }
 
         let to = self
             .0
             .get_mut(instruction.to)
-            .ok_or_else(|| anyhow!("to index too large: {}", instruction.to))?;
+            .ok_or_else(|| anyhow!("there is no stack {}", instruction.to + 1))?;
 
         to.extend(tmp.into_iter().rev());
 
@@ -64,14 +75,50 @@ impl CrateStack {
     }
 }
 
-#[derive(Display, FromStr, Clone, Copy, PartialEq, Eq)]
-#[display("move {count} from {from} to {to}")]
+/// A move between stacks, which are numbered from 0 here but from 1 in the input.
+#[derive(Clone, Copy, PartialEq, Eq)]
 struct Instruction {
     count: usize,
     from: usize,
     to: usize,
 }
 
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    /// Parse `move 1 from 2 to 1`, turning the 1-based stack numbers into indices.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = Words::new(s);
+        let stack = |words: &mut Words| -> Result<usize, ParseError> {
+            let number: usize = words.number()?;
+            number
+                .checked_sub(1)
+                .ok_or_else(|| ParseError::at(s, words.last_span(), "a stack number from 1"))
+        };
+
+        words.keyword("move")?;
+        let count = words.number()?;
+        words.keyword("from")?;
+        let from = stack(&mut words)?;
+        words.keyword("to")?;
+        let to = stack(&mut words)?;
+        words.end()?;
+        Ok(Instruction { count, from, to })
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count,
+            self.from + 1,
+            self.to + 1
+        )
+    }
+}
+
 impl std::fmt::Debug for Instruction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         format!("{self}").fmt(f)
@@ -108,8 +155,25 @@ impl FromStr for StackSlice {
     }
 }
 
-#[aoc_generator(day5)]
-fn parse_data(input: &str) -> Result<Data> {
+pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(5, parse_data)
+            .normalize(Normalize::INDENTED)
+            .part1(part1)
+            .part2(part2)
+            .cross_check(
+                "single crate moves",
+                &[
+                    ("part1", |d| part1(d).map(Some)),
+                    ("part2 one crate at a time", |d| {
+                        part2_one_crate_at_a_time(d).map(Some)
+                    }),
+                ],
+            ),
+    )
+}
+
+fn parse_data(input: &str) -> Result<Data, ParseError> {
     let mut stacks: Vec<Vec<char>> = vec![];
     let mut instructions = vec![];
 
@@ -121,7 +185,7 @@ fn parse_data(input: &str) -> Result<Data> {
 
     let mut mode = Mode::Crates;
 
-    for line in input.lines() {
+    for (index, line) in input.lines().enumerate() {
         match mode {
             Mode::Crates => {
                 if let Ok(slice) = line.parse::<StackSlice>() {
@@ -135,26 +199,30 @@ fn parse_data(input: &str) -> Result<Data> {
                             stacks[index].push(c);
                         }
                     }
-                } else if is_label_list(line, stacks.len()) {
+                } else if let Some(count) = label_count(line).filter(|&n| n >= stacks.len()) {
+                    // Stacks that are empty all the way up only show up in the labels.
+                    stacks.resize(count, vec![]);
                     mode = Mode::Empty;
                 } else {
-                    Err(anyhow!("unexpected line: {line:?}"))?
+                    Err(ParseError::at(
+                        line,
+                        trimmed(line),
+                        "a row of crates like `[A] [B]` or the stack numbers",
+                    )
+                    .offset_lines(index))?
                 }
             }
             Mode::Empty => {
                 if line.is_empty() {
                     mode = Mode::Instructions;
                 } else {
-                    Err(anyhow!("expected empty line"))?
+                    Err(ParseError::at(line, 0..line.len(), "an empty line").offset_lines(index))?
                 }
             }
             Mode::Instructions => {
-                let mut instr: Instruction = line.parse()?;
-                if instr.from == 0 || instr.to == 0 {
-                    Err(anyhow!("index not 1-based"))?
-                };
-                instr.from -= 1;
-                instr.to -= 1;
+                let instr = line
+                    .parse::<Instruction>()
+                    .map_err(|e| e.offset_lines(index))?;
                 instructions.push(instr);
             }
         }
@@ -165,40 +233,90 @@ fn parse_data(input: &str) -> Result<Data> {
     }
 
     let stacks = CrateStack(stacks);
+    debug!(
+        stacks = stacks.0.len(),
+        instructions = instructions.len(),
+        "parsed"
+    );
     Ok(Data {
         stacks,
         instructions,
     })
 }
 
-fn is_label_list(_line: &str, _len: usize) -> bool {
-    //todo!()
-    true
+/// The number of stacks, if `line` labels them `1` to `n`.
+fn label_count(line: &str) -> Option<usize> {
+    let mut count = 0;
+    for label in line.split_whitespace() {
+        if label.parse::<usize>().ok() != Some(count + 1) {
+            return None;
+        }
+        count += 1;
+    }
+    Some(count)
+}
+
+impl std::fmt::Display for Data {
+    /// Draw the input this was parsed from.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let stacks = &self.stacks.0;
+        let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+        for row in (0..height).rev() {
+            let line = stacks
+                .iter()
+                .map(|stack| stack.get(row).map_or("   ".into(), |c| format!("[{c}]")))
+                .join(" ");
+            writeln!(f, "{line}")?;
+        }
+        let labels = (1..=stacks.len()).map(|n| format!(" {n} ")).join(" ");
+        writeln!(f, "{labels}")?;
+        writeln!(f)?;
+        for instruction in &self.instructions {
+            writeln!(f, "{instruction}")?;
+        }
+        Ok(())
+    }
 }
-#[aoc(day5, part1)]
 fn part1(data: &Data) -> Result<String> {
     let mut stacks = data.stacks.clone();
     for i in data.instructions.iter().cloned() {
-        stacks.apply_instruction_part1(i)?;
+        stacks
+            .apply_instruction_part1(i)
+            .with_context(|| i.to_string())?;
+        trace!(instruction = %i, tops = stacks.get_tops_as_string(), "moved");
     }
 
     Ok(stacks.get_tops_as_string())
 }
 
-#[aoc(day5, part2)]
 fn part2(data: &Data) -> Result<String> {
     let mut stacks = data.stacks.clone();
     for i in data.instructions.iter().cloned() {
-        stacks.apply_instruction_part2(i)?;
+        stacks
+            .apply_instruction_part2(i)
+            .with_context(|| i.to_string())?;
+        trace!(instruction = %i, tops = stacks.get_tops_as_string(), "moved");
     }
 
     Ok(stacks.get_tops_as_string())
 }
 
+/// Part 2's mover, given part 1's moves of one crate each, should end up like part 1.
+fn part2_one_crate_at_a_time(data: &Data) -> Result<String> {
+    let mut stacks = data.stacks.clone();
+    for i in data.instructions.iter().cloned() {
+        for _ in 0..i.count {
+            stacks.apply_instruction_part2(Instruction { count: 1, ..i })?;
+        }
+    }
+    Ok(stacks.get_tops_as_string())
+}
+
 #[cfg(test)]
 mod test {
-    use super::{parse_data, part1, part2};
+    use super::{parse_data, part1, part2, CrateStack, Data, Instruction};
     use anyhow::Result;
+    use proptest::prelude::*;
 
     const EXAMPLE_INPUT: &str = include_str!("../input/2022/day5.example.txt");
 
@@ -213,4 +331,59 @@ mod test {
         assert_eq!(part2(&parse_data(EXAMPLE_INPUT)?)?, "MCD".to_string());
         Ok(())
     }
+
+    #[test]
+    fn parse_error_location() {
+        let input = EXAMPLE_INPUT.replace("move 1 from 1 to 2", "move 1 from 0 to 2");
+        let error = parse_data(&input).expect_err("should not parse");
+        assert_eq!((error.line, error.column), (9, 13));
+        assert_eq!(error.expected, "a stack number from 1");
+
+        let input = EXAMPLE_INPUT.replacen("\n\n", "\nx\n", 1);
+        let error = parse_data(&input).expect_err("should not parse");
+        assert_eq!((error.line, error.column), (5, 1));
+        assert_eq!(error.expected, "an empty line");
+    }
+
+    #[test]
+    fn example_round_trip() -> Result<()> {
+        assert_eq!(parse_data(EXAMPLE_INPUT)?.to_string(), EXAMPLE_INPUT);
+        Ok(())
+    }
+
+    fn instruction(stacks: usize) -> impl Strategy<Value = Instruction> {
+        (any::<usize>(), 0..stacks, 0..stacks).prop_map(|(count, from, to)| Instruction {
+            count,
+            from,
+            to,
+        })
+    }
+
+    /// Up to 9 stacks, so the labels stay one digit wide.
+    fn data() -> impl Strategy<Value = Data> {
+        (1..=9usize)
+            .prop_flat_map(|stacks| {
+                let stack = prop::collection::vec(prop::char::range('A', 'Z'), 0..6);
+                (
+                    prop::collection::vec(stack, stacks),
+                    prop::collection::vec(instruction(stacks), 0..10),
+                )
+            })
+            .prop_map(|(stacks, instructions)| Data {
+                stacks: CrateStack(stacks),
+                instructions,
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn instruction_round_trip(instruction in instruction(1000)) {
+            prop_assert_eq!(instruction.to_string().parse::<Instruction>()?, instruction);
+        }
+
+        #[test]
+        fn data_round_trip(data in data()) {
+            prop_assert_eq!(parse_data(&data.to_string())?, data);
+        }
+    }
 }
@@ -1,10 +1,35 @@
 use std::collections::VecDeque;
 
 use anyhow::{anyhow, Result};
-use aoc_runner_derive::aoc;
 use itertools::Itertools;
+use tracing::debug;
+
+use crate::{
+    normalize::Normalize,
+    parse::ParseError,
+    solution::{Day, Part, Solution},
+    stream::Stream,
+};
+
+pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(6, parse_data)
+            .normalize(Normalize::TRIM)
+            .solver(Part::One, Some("A_Tuples"), |d| part1_tuples(d))
+            .solver(Part::One, Some("B_Generic"), |d| part1_generic(d))
+            .part2(|d| part2(d))
+            .stream(Part::One, |s| stream_marker_position(s, 4))
+            .stream(Part::Two, |s| stream_marker_position(s, 14)),
+    )
+}
+
+/// The datastream is the first line.
+fn parse_data(input: &str) -> Result<String, ParseError> {
+    let stream = input.lines().next().unwrap_or_default();
+    debug!(length = stream.len(), "parsed");
+    Ok(stream.to_owned())
+}
 
-#[aoc(day6, part1, A_Tuples)]
 fn part1_tuples(data: &str) -> Result<usize> {
     let index = data
         .chars()
@@ -18,19 +43,24 @@ fn part1_tuples(data: &str) -> Result<usize> {
     Ok(index)
 }
 
-#[aoc(day6, part1, B_Generic)]
 fn part1_generic(data: &str) -> Result<usize> {
     find_marker_position(data, 4)
 }
 
-#[aoc(day6, part2)]
 fn part2(data: &str) -> Result<usize> {
     find_marker_position(data, 14)
 }
 
 fn find_marker_position(data: &str, marker_length: usize) -> Result<usize> {
-    let index = data
-        .chars()
+    find_marker(data.chars(), marker_length)
+}
+
+fn stream_marker_position(stream: Stream<'_>, marker_length: usize) -> Result<usize> {
+    itertools::process_results(stream.chars(), |chars| find_marker(chars, marker_length))?
+}
+
+fn find_marker(chars: impl Iterator<Item = char>, marker_length: usize) -> Result<usize> {
+    let index = chars
         .windows(marker_length)
         .enumerate()
         .find_map(|(ix, mut window)| window.all_unique().then_some(ix + marker_length))
@@ -71,7 +101,7 @@ impl<T: Clone, I: Iterator<Item = T>> Iterator for IterWindows<T, I> {
     fn next(&mut self) -> Option<Self::Item> {
         self.buf.pop_front(); // discard first element.
         while self.buf.len() < self.size {
-            let Some(value) = self.it.next() else { return None;};
+            let value = self.it.next()?;
             self.buf.push_back(value)
         }
         Some(self.buf.iter().cloned().collect::<Vec<_>>().into_iter())
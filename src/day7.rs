@@ -1,8 +1,17 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Range, str::FromStr};
 
 use anyhow::{anyhow, Result};
-use aoc_runner_derive::{aoc, aoc_generator};
-use parse_display::{Display, FromStr};
+use parse_display::Display;
+use tracing::trace;
+
+use crate::{
+    checked::Checked,
+    normalize::Normalize,
+    parse::{trimmed, ParseError, Words},
+    solution::{Day, Solution},
+};
+
+const CHECKED: Checked = Checked::day(7);
 
 #[derive(Debug, Default, Clone)]
 struct InputTreeLevel(HashMap<String, TreeNode>);
@@ -22,7 +31,7 @@ enum TreeNode {
     File(usize),
 }
 
-#[derive(Debug, Display, FromStr)]
+#[derive(Debug, Display, Clone, PartialEq, Eq)]
 enum InputLine {
     #[display("$ cd /")]
     CdRoot(),
@@ -38,36 +47,93 @@ enum InputLine {
     Dir(String),
 }
 
-#[aoc_generator(day7)]
-fn parse_data(input: &str) -> Result<InputTree> {
-    let input_lines = input.lines().map(|l| l.trim().parse::<InputLine>());
+impl FromStr for InputLine {
+    type Err = ParseError;
 
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = Words::new(s);
+        let line = match words.next("a command, a directory or a file")? {
+            "$" => match words.next("`cd` or `ls`")? {
+                "cd" => match words.rest("a directory")? {
+                    "/" => InputLine::CdRoot(),
+                    ".." => InputLine::CdUp(),
+                    dir => InputLine::Cd(dir.to_string()),
+                },
+                "ls" => InputLine::Ls(),
+                _ => Err(ParseError::at(s, words.last_span(), "`cd` or `ls`"))?,
+            },
+            "dir" => InputLine::Dir(words.rest("a directory name")?.to_string()),
+            size => {
+                let size = size.parse().map_err(|_| {
+                    ParseError::at(s, words.last_span(), "`$`, `dir` or a file size")
+                })?;
+                InputLine::File(size, words.rest("a file name")?.to_string())
+            }
+        };
+        words.end()?;
+        Ok(line)
+    }
+}
+
+pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(7, parse_data)
+            .normalize(Normalize::TRIM)
+            .part1(part1)
+            .part2(part2),
+    )
+}
+
+fn parse_data(input: &str) -> Result<InputTree, ParseError> {
     let mut tree = InputTreeLevel::new();
     let mut path = vec![];
 
-    for line in input_lines {
-        let line = line?;
+    for (index, line) in input.lines().enumerate() {
+        let error = |span: Range<usize>, expected: &str| {
+            ParseError::at(line, span, expected).offset_lines(index)
+        };
+        // The directory of a `cd` is what ends its line.
+        let cd_target = |dir: &str| {
+            let end = trimmed(line).end;
+            end - dir.len()..end
+        };
 
-        match line {
+        match line
+            .parse()
+            .map_err(|e: ParseError| e.offset_lines(index))?
+        {
             InputLine::CdRoot() => {
                 path.clear();
+                trace!(?path, "cd");
             }
             InputLine::CdUp() => {
-                let element = path.pop();
-                assert!(element.is_some());
+                if path.pop().is_none() {
+                    Err(error(
+                        cd_target(".."),
+                        "a directory to enter (already at `/`)",
+                    ))?;
+                }
+                trace!(?path, "cd");
             }
             InputLine::Cd(dir) => {
                 path.push(dir);
+                if find_dir(&path, &mut tree).is_none() {
+                    let dir = path.pop().unwrap_or_default();
+                    Err(error(cd_target(&dir), "a directory listed by `ls`"))?;
+                }
+                trace!(?path, "cd");
             }
             InputLine::Ls() => {
                 // Ignore
             }
             InputLine::File(size, file_name) => {
-                let here = find_dir(&path, &mut tree)?;
+                let here =
+                    find_dir(&path, &mut tree).ok_or_else(|| error(trimmed(line), "a listing"))?;
                 here.0.insert(file_name, TreeNode::File(size));
             }
             InputLine::Dir(dir_name) => {
-                let here = find_dir(&path, &mut tree)?;
+                let here =
+                    find_dir(&path, &mut tree).ok_or_else(|| error(trimmed(line), "a listing"))?;
                 here.0
                     .insert(dir_name, TreeNode::Dir(InputTreeLevel::new()));
             }
@@ -77,18 +143,17 @@ fn parse_data(input: &str) -> Result<InputTree> {
     Ok(InputTree(tree))
 }
 
-fn find_dir<'a>(
-    path: &'a [String],
-    tree: &'a mut InputTreeLevel,
-) -> Result<&'a mut InputTreeLevel> {
+fn find_dir<'a>(path: &[String], tree: &'a mut InputTreeLevel) -> Option<&'a mut InputTreeLevel> {
     let mut here = tree;
-    for dir in path.iter().cloned() {
-        let TreeNode::Dir(next) = here.0.get_mut(&dir).ok_or_else(||anyhow!("could not navigate to path {path:?}"))? else {Err(anyhow!("wrong type of node"))?};
+    for dir in path {
+        let TreeNode::Dir(next) = here.0.get_mut(dir)? else {
+            return None;
+        };
 
         here = next;
     }
 
-    Ok(here)
+    Some(here)
 }
 
 #[derive(Debug)]
@@ -97,44 +162,45 @@ struct SizeCalcLevel {
     inner: HashMap<String, SizeCalcLevel>,
 }
 
-impl From<InputTreeLevel> for SizeCalcLevel {
-    fn from(i: InputTreeLevel) -> Self {
+impl TryFrom<InputTreeLevel> for SizeCalcLevel {
+    type Error = anyhow::Error;
+
+    fn try_from(i: InputTreeLevel) -> Result<Self> {
         let mut total = 0;
-        let inner =
-            i.0.into_iter()
-                .filter_map(|(name, node)| match node {
-                    TreeNode::Dir(inner) => {
-                        let inner = Self::from(inner);
-                        total += inner.size;
-                        Some((name, inner))
-                    }
-                    TreeNode::File(size) => {
-                        total += size;
-                        None
-                    }
-                })
-                .collect();
-        Self { size: total, inner }
+        let mut inner = HashMap::new();
+        for (name, node) in i.0 {
+            match node {
+                TreeNode::Dir(dir) => {
+                    let dir = Self::try_from(dir)?;
+                    total = CHECKED.add("directory size", total, dir.size)?;
+                    inner.insert(name, dir);
+                }
+                TreeNode::File(size) => {
+                    total = CHECKED.add("directory size", total, size)?;
+                }
+            }
+        }
+        Ok(Self { size: total, inner })
     }
 }
 
 impl SizeCalcLevel {
-    fn get_sum_le(&self, limit: usize) -> usize {
-        let mut total = if self.size <= limit { self.size } else { 0 };
+    fn get_sum_le(&self, limit: usize) -> Result<usize> {
+        let own = if self.size <= limit { self.size } else { 0 };
+        let inner = self
+            .inner
+            .values()
+            .map(|inner| inner.get_sum_le(limit))
+            .collect::<Result<Vec<_>>>()?;
 
-        for (_, inner) in self.inner.iter() {
-            total += inner.get_sum_le(limit);
-        }
-
-        total
+        CHECKED.sum("sum of small directories", [own].into_iter().chain(inner))
     }
 }
 
-#[aoc(day7, part1)]
 fn part1(data: &InputTree) -> Result<usize> {
     let input_data = data.0.clone();
-    let tree: SizeCalcLevel = input_data.into();
-    Ok(tree.get_sum_le(100000))
+    let tree: SizeCalcLevel = input_data.try_into()?;
+    tree.get_sum_le(100000)
 }
 
 impl SizeCalcLevel {
@@ -157,15 +223,14 @@ impl SizeCalcLevel {
     }
 }
 
-#[aoc(day7, part2)]
 fn part2(data: &InputTree) -> Result<usize> {
-    let tree: SizeCalcLevel = data.0.clone().into();
+    let tree: SizeCalcLevel = data.0.clone().try_into()?;
 
     let total_disk = 70000000;
-    let needed_disk = 30000000;
+    let needed_disk: usize = 30000000;
     let root_size = tree.size;
-    let available_disk = total_disk - root_size;
-    let needed_cleanup = needed_disk - available_disk;
+    let available_disk = CHECKED.sub("total_disk - root_size", total_disk, root_size)?;
+    let needed_cleanup = needed_disk.saturating_sub(available_disk);
 
     tree.find_smallest_ge(needed_cleanup)
         .ok_or_else(|| anyhow!("no candidate found"))
@@ -173,8 +238,9 @@ fn part2(data: &InputTree) -> Result<usize> {
 
 #[cfg(test)]
 mod test {
-    use super::{parse_data, part1, part2};
+    use super::{parse_data, part1, part2, InputLine};
     use anyhow::Result;
+    use proptest::prelude::*;
 
     const EXAMPLE_INPUT: &str = include_str!("../input/2022/day7.example.txt");
 
@@ -189,4 +255,53 @@ mod test {
         assert_eq!(part2(&parse_data(EXAMPLE_INPUT)?)?, 24933642);
         Ok(())
     }
+
+    #[test]
+    fn part2_with_room_to_spare() -> Result<()> {
+        assert_eq!(part2(&parse_data("$ cd /\n$ ls\n100 a.txt\n")?)?, 100);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_bigger_than_disk() -> Result<()> {
+        let error =
+            part2(&parse_data("$ cd /\n$ ls\n80000000 a.txt\n")?).expect_err("should underflow");
+        assert_eq!(
+            error.to_string(),
+            "day 7, total_disk - root_size: 70000000 - 80000000 doesn't fit in usize"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_error_location() {
+        let error = parse_data("$ cd /\n$ ls\ndir a\n$ cd b\n").expect_err("should not parse");
+        assert_eq!((error.line, error.column), (4, 6));
+        assert_eq!(error.expected, "a directory listed by `ls`");
+
+        let error = parse_data("$ cd /\n$ ls\n12x b.txt\n").expect_err("should not parse");
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.found, "\"12x\"");
+    }
+
+    /// Names that can't be mistaken for `/` or `..`.
+    const NAME: &str = "[a-z][a-z.]{0,8}";
+
+    fn input_line() -> impl Strategy<Value = InputLine> {
+        prop_oneof![
+            Just(InputLine::CdRoot()),
+            Just(InputLine::CdUp()),
+            NAME.prop_map(InputLine::Cd),
+            Just(InputLine::Ls()),
+            (any::<usize>(), NAME).prop_map(|(size, name)| InputLine::File(size, name)),
+            NAME.prop_map(InputLine::Dir),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn input_line_round_trip(line in input_line()) {
+            prop_assert_eq!(line.to_string().parse::<InputLine>()?, line);
+        }
+    }
 }
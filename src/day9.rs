@@ -1,9 +1,17 @@
-use std::{collections::HashSet, ops::Not, str::FromStr};
+use std::{collections::HashSet, str::FromStr};
 
-use anyhow::{Error, Result};
-use aoc_runner_derive::{aoc, aoc_generator};
+use anyhow::Result;
 use itertools::Itertools;
-use parse_display::{Display, FromStr};
+use parse_display::Display;
+use tracing::{debug, trace};
+
+use crate::{
+    geom::{BoundsXY, DeltaXY, PosXY},
+    normalize::Normalize,
+    parse::{ParseError, Words},
+    solution::{Day, Part, Solution},
+    stream::Stream,
+};
 
 #[derive(Debug)]
 struct Input {
@@ -11,35 +19,52 @@ struct Input {
 }
 
 impl FromStr for Input {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self> {
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         let list = s
             .lines()
-            .filter_map(|x| {
-                let x = x.trim();
-                x.is_empty().not().then_some(x)
-            })
-            .map(|x| Ok(x.parse::<Move>()?))
-            .collect::<Result<Vec<Move>>>()?;
+            .enumerate()
+            .filter(|(_, x)| !x.trim().is_empty())
+            .map(|(index, x)| x.parse::<Move>().map_err(|e| e.offset_lines(index)))
+            .collect::<Result<Vec<Move>, _>>()?;
         Ok(Self { list })
     }
 }
 
-#[derive(Display, FromStr, Clone, Copy, PartialEq, Eq)]
+#[derive(Display, Clone, Copy, PartialEq, Eq)]
 #[display("{direction} {steps}")]
 struct Move {
     direction: Direction,
     steps: usize,
 }
 
+impl FromStr for Move {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = Words::new(s);
+        let expected = "a direction `U`, `D`, `L` or `R`";
+        let direction = match words.next(expected)? {
+            "U" => Direction::Up,
+            "D" => Direction::Down,
+            "L" => Direction::Left,
+            "R" => Direction::Right,
+            _ => Err(ParseError::at(s, words.last_span(), expected))?,
+        };
+        let steps = words.number()?;
+        words.end()?;
+        Ok(Self { direction, steps })
+    }
+}
+
 impl std::fmt::Debug for Move {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "<{} {}>", self.direction, self.steps)
     }
 }
 
-#[derive(Display, FromStr, Clone, Copy, PartialEq, Eq)]
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
 enum Direction {
     #[display("U")]
     Up,
@@ -62,100 +87,127 @@ impl Direction {
     }
 }
 
-#[aoc_generator(day9)]
-fn parse_data(input: &str) -> Result<Input> {
-    Ok(input.parse()?)
+pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(9, parse_data)
+            .normalize(Normalize::TRIM)
+            .part1(part1)
+            .part2(part2)
+            .stream(Part::One, part1_stream)
+            .stream(Part::Two, part2_stream),
+    )
 }
 
-#[aoc(day9, part1)]
-fn part1(data: &Input) -> Result<usize> {
-    let mut covered = HashSet::<PosXY>::new();
+fn parse_data(input: &str) -> Result<Input, ParseError> {
+    let input: Input = input.parse()?;
+    debug!(moves = input.list.len(), "parsed");
+    Ok(input)
+}
+
+/// A rope of knots following its head, and the positions its tail has covered.
+struct Rope {
+    knots: Vec<PosXY>,
+    covered: HashSet<PosXY>,
+}
 
-    let mut head = PosXY::default();
-    let mut tail = PosXY::default();
+impl Rope {
+    fn new(length: usize) -> Self {
+        Self {
+            knots: vec![PosXY::default(); length],
+            covered: HashSet::new(),
+        }
+    }
 
-    for m in data.list.iter().cloned() {
+    fn apply(&mut self, m: Move) {
         let delta = m.direction.delta();
         for _ in 0..m.steps {
-            head += delta;
-            tail.move_towards(head);
-            covered.insert(tail);
+            self.knots[0] += delta;
+            for i in 1..self.knots.len() {
+                let target = self.knots[i - 1];
+                follow(&mut self.knots[i], target);
+            }
+            self.covered.insert(self.knots[self.knots.len() - 1]);
+        }
+        match self.knots[..] {
+            [head, tail] => trace!(%m, ?head, ?tail, "moved"),
+            _ => trace!(%m, "moved:\n{}", plot(&self.knots, &self.covered)),
         }
     }
+}
 
-    Ok(covered.len())
+/// Move `knot` until it touches `target`, diagonally if they aren't in line.
+fn follow(knot: &mut PosXY, target: PosXY) {
+    while (target - *knot).max_norm() > 1 {
+        *knot += (target - *knot).signum();
+    }
+}
+
+fn part1(data: &Input) -> Result<usize> {
+    let mut rope = Rope::new(2);
+    data.list.iter().for_each(|&m| rope.apply(m));
+    Ok(rope.covered.len())
 }
 
 const ROPE_LENGTH: usize = 10;
 
-#[aoc(day9, part2)]
 fn part2(data: &Input) -> Result<usize> {
-    let mut covered = HashSet::<PosXY>::new();
-
-    let mut rope = Vec::new();
-    for _ in 0..ROPE_LENGTH {
-        rope.push(PosXY::default());
-    }
+    let mut rope = Rope::new(ROPE_LENGTH);
+    data.list.iter().for_each(|&m| rope.apply(m));
+    Ok(rope.covered.len())
+}
 
-    for m in data.list.iter().cloned() {
-        let delta = m.direction.delta();
-        for _ in 0..m.steps {
-            rope[0] += delta;
-            for i in 1..ROPE_LENGTH {
-                let target = rope[i - 1];
-                rope[i].move_towards(target);
-            }
-            covered.insert(rope.last().unwrap().clone());
+/// Move `rope` as the moves are read, skipping empty lines like [`Input`] does.
+fn stream_moves(stream: Stream<'_>, mut rope: Rope) -> Result<usize> {
+    stream.for_each_line(|index, line| {
+        if !line.trim().is_empty() {
+            rope.apply(
+                line.parse()
+                    .map_err(|e: ParseError| e.offset_lines(index))?,
+            );
         }
-        // println!("After move {m}");
-        // plot(&rope, &covered);
-        // println!();
-    }
+        Ok(())
+    })?;
+    Ok(rope.covered.len())
+}
+
+fn part1_stream(stream: Stream<'_>) -> Result<usize> {
+    stream_moves(stream, Rope::new(2))
+}
 
-    Ok(covered.len())
+fn part2_stream(stream: Stream<'_>) -> Result<usize> {
+    stream_moves(stream, Rope::new(ROPE_LENGTH))
 }
 
-#[allow(unused)]
-fn plot(rope: &Vec<PosXY>, covered: &HashSet<PosXY>) {
-    let mut min_x = 0;
-    let mut max_x = 0;
-    let mut min_y = 0;
-    let mut max_y = 0;
-
-    for pos in rope.iter().chain(covered.iter()).cloned() {
-        max_x = max_x.max(pos.x);
-        max_y = max_y.max(pos.y);
-        min_x = min_x.min(pos.x);
-        min_y = min_y.min(pos.y);
+/// Draw the rope over the positions the tail has covered, head as `H` and knots by number.
+fn plot(rope: &[PosXY], covered: &HashSet<PosXY>) -> String {
+    let mut bounds = BoundsXY::new(PosXY::default());
+    for &pos in rope.iter().chain(covered.iter()) {
+        bounds.include(pos);
     }
 
-    for y in (min_y..=max_y).rev() {
-        for x in min_x..=max_x {
+    let mut out = String::new();
+    for y in (bounds.min.y..=bounds.max.y).rev() {
+        for x in bounds.min.x..=bounds.max.x {
             let pos = PosXY::new(x, y);
-            if let Some(i) = rope.iter().find_position(|p| pos == **p).map(|(i, _)| i) {
-                match i {
-                    0 => {
-                        print!("H");
-                    }
-                    1..=9 => {
-                        print!("{i}");
-                    }
-                    _ => panic!("unexpected"),
-                }
-            } else if covered.contains(&pos) {
-                print!("#");
-            } else {
-                print!(".");
-            }
-        }
-        println!();
-    }
+            out.push(
+                match rope.iter().find_position(|p| pos == **p).map(|(i, _)| i) {
+                    Some(0) => 'H',
+                    Some(i) => char::from_digit(i as u32 % 10, 10).unwrap_or('?'),
+                    None if covered.contains(&pos) => '#',
+                    None => '.',
+                },
+            );
+        }
+        out.push('\n');
+    }
+    out
 }
 
 #[cfg(test)]
 mod test {
-    use super::{parse_data, part1, part2};
+    use super::{parse_data, part1, part2, Direction, Move};
     use anyhow::Result;
+    use proptest::prelude::*;
 
     const EXAMPLE_INPUT: &str = include_str!("../input/2022/day9.example.txt");
     const EXAMPLE2_INPUT: &str = include_str!("../input/2022/day9.example2.txt");
@@ -177,111 +229,21 @@ mod test {
         assert_eq!(part2(&parse_data(EXAMPLE2_INPUT)?)?, 36);
         Ok(())
     }
-}
 
-use pos::*;
-mod pos {
-    use super::delta::*;
-    use std::ops::*;
-
-    #[derive(Hash, Clone, Copy, PartialEq, Eq, Default)]
-    pub struct PosXY {
-        pub x: isize,
-        pub y: isize,
-    }
-
-    impl PosXY {
-        pub fn new(x: isize, y: isize) -> Self {
-            Self { x, y }
-        }
-        pub fn move_towards(&mut self, other: PosXY) {
-            while (other - *self).max_norm() > 1 {
-                *self += (other - *self).limit_to_1();
-            }
-        }
-    }
-
-    impl Add<DeltaXY> for PosXY {
-        type Output = Self;
-        fn add(self, rhs: DeltaXY) -> Self::Output {
-            Self::new(self.x + rhs.x, self.y + rhs.y)
-        }
-    }
-
-    impl Sub<DeltaXY> for PosXY {
-        type Output = Self;
-        fn sub(self, rhs: DeltaXY) -> Self::Output {
-            Self::new(self.x - rhs.x, self.y - rhs.y)
-        }
-    }
-
-    impl Sub<PosXY> for PosXY {
-        type Output = DeltaXY;
-        fn sub(self, rhs: PosXY) -> Self::Output {
-            DeltaXY::new(self.x - rhs.x, self.y - rhs.y)
-        }
-    }
-
-    impl AddAssign<DeltaXY> for PosXY {
-        fn add_assign(&mut self, rhs: DeltaXY) {
-            *self = *self + rhs;
-        }
-    }
-
-    impl SubAssign<DeltaXY> for PosXY {
-        fn sub_assign(&mut self, rhs: DeltaXY) {
-            *self = *self - rhs;
-        }
-    }
-}
-
-use delta::*;
-mod delta {
-    use std::ops::*;
-
-    #[derive(Hash, Clone, Copy, PartialEq, Eq, Default)]
-    pub struct DeltaXY {
-        pub x: isize,
-        pub y: isize,
-    }
-
-    impl DeltaXY {
-        pub fn new(x: isize, y: isize) -> Self {
-            Self { x, y }
-        }
-        pub fn max_norm(&self) -> usize {
-            let dx = self.x.abs() as usize;
-            let dy = self.y.abs() as usize;
-            dx.max(dy)
-        }
-        pub fn limit_to_1(&self) -> Self {
-            Self::new(self.x.min(1).max(-1), self.y.min(1).max(-1))
-        }
-    }
-
-    impl Add for DeltaXY {
-        type Output = Self;
-        fn add(self, rhs: Self) -> Self::Output {
-            Self::new(self.x + rhs.x, self.y + rhs.y)
-        }
-    }
-
-    impl Sub for DeltaXY {
-        type Output = Self;
-        fn sub(self, rhs: Self) -> Self::Output {
-            Self::new(self.x - rhs.x, self.y - rhs.y)
-        }
-    }
-
-    impl AddAssign for DeltaXY {
-        fn add_assign(&mut self, rhs: Self) {
-            *self = *self + rhs;
-        }
+    fn moves() -> impl Strategy<Value = Move> {
+        let direction = prop_oneof![
+            Just(Direction::Up),
+            Just(Direction::Down),
+            Just(Direction::Left),
+            Just(Direction::Right),
+        ];
+        (direction, any::<usize>()).prop_map(|(direction, steps)| Move { direction, steps })
     }
 
-    impl SubAssign for DeltaXY {
-        fn sub_assign(&mut self, rhs: Self) {
-            *self = *self - rhs;
+    proptest! {
+        #[test]
+        fn move_round_trip(m in moves()) {
+            prop_assert_eq!(m.to_string().parse::<Move>()?, m);
         }
     }
 }
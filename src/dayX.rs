@@ -1,29 +1,38 @@
 use anyhow::Result;
-use aoc_runner_derive::{aoc, aoc_generator};
 
-struct Data(Vec<usize>);
+use crate::{
+    normalize::Normalize,
+    parse::{number, trimmed, ParseError},
+    solution::{Day, Solution},
+};
+
+pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(X, parse_data)
+            .year(YEAR)
+            .normalize(Normalize::TRIM)
+            .part1(|d| part1(d))
+            .part2(|d| part2(d)),
+    )
+}
 
-#[aoc_generator(dayX)]
-fn parse_data(input: &str) -> Result<Vec<Data>> {
+fn parse_data(input: &str) -> Result<Vec<usize>, ParseError> {
     let mut data = vec![];
-    for line in input.lines() {
-        let line = line.trim();
-        if line.is_empty() {
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
             continue;
         }
-        todo!();
+        data.push(number(line, trimmed(line)).map_err(|e| e.offset_lines(index))?);
     }
     Ok(data)
 }
 
-#[aoc(dayX, part1)]
-fn part1(data: &[Data]) -> Result<usize> {
-    todo!()
+fn part1(data: &[usize]) -> Result<usize> {
+    todo!("part 1 for {} lines", data.len())
 }
 
-#[aoc(dayX, part2)]
-fn part2(data: &[Data]) -> Result<usize> {
-    todo!()
+fn part2(data: &[usize]) -> Result<usize> {
+    todo!("part 2 for {} lines", data.len())
 }
 
 #[cfg(test)]
@@ -31,7 +40,7 @@ mod test {
     use super::{parse_data, part1, part2};
     use anyhow::Result;
 
-    const EXAMPLE_INPUT: &str = "example"; // --or--  include_str!("../input/2022/dayX.example.txt");
+    const EXAMPLE_INPUT: &str = include_str!("../input/YEAR/dayX.example.txt");
 
     #[test]
     #[ignore = "not implemented yet"]
@@ -0,0 +1,393 @@
+//! Differential testing: implementations that must agree are run on generated inputs, and
+//! the first input they disagree on is shrunk to a small reproducer.
+//!
+//! Two kinds of implementations are compared: the variants of a part, and the cross-checks
+//! a day registers, listed by [`Solution::cross_checks`].
+
+use std::{fmt::Display, ops::Range};
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use tracing::{debug, info};
+
+use crate::{
+    generate::{Generator, ParamValue},
+    solution::{Answer, Part, Solution},
+};
+
+/// A set of implementations of one day that must agree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// The variants of a part, like day 6's `A_Tuples` and `B_Generic`.
+    Variants(Part),
+    /// A cross-check registered by the day.
+    CrossCheck(&'static str),
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Check::Variants(part) => write!(f, "the variants of part {part}"),
+            Check::CrossCheck(name) => write!(f, "the {name:?} cross-check"),
+        }
+    }
+}
+
+/// What each implementation of a check gave, by name; errors are kept as text.
+pub type Outcomes = Vec<(String, Result<Answer, String>)>;
+
+/// The checks of a day: the parts with more than one variant, and its cross-checks.
+pub fn checks(solution: &dyn Solution) -> Vec<Check> {
+    let variants = solution.variants();
+    [Part::One, Part::Two]
+        .into_iter()
+        .filter(|&part| variants.iter().filter(|v| v.part == part).count() > 1)
+        .map(Check::Variants)
+        .chain(solution.cross_checks().into_iter().map(Check::CrossCheck))
+        .collect()
+}
+
+/// Run every implementation of `check` on `input`, or `None` if the input doesn't parse or
+/// an implementation can't handle it.
+pub fn outcomes(solution: &dyn Solution, check: Check, input: &str) -> Option<Outcomes> {
+    let data = solution.parse(input).ok()?;
+    match check {
+        Check::Variants(part) => Some(
+            solution
+                .variants()
+                .into_iter()
+                .filter(|v| v.part == part)
+                .map(|v| {
+                    let answer = solution.solve(&data, v).map_err(|e| format!("{e:#}"));
+                    (v.to_string(), answer)
+                })
+                .collect(),
+        ),
+        Check::CrossCheck(name) => solution
+            .run_cross_check(&data, name)
+            .ok()?
+            .into_iter()
+            .map(|(name, answer)| match answer {
+                Ok(Some(answer)) => Some((name.to_string(), Ok(answer))),
+                Ok(None) => None,
+                Err(e) => Some((name.to_string(), Err(format!("{e:#}")))),
+            })
+            .collect(),
+    }
+}
+
+/// Whether the answers differ, or some implementations fail where others don't. When all of
+/// them fail, they agree that the input is bad.
+pub fn disagree(outcomes: &Outcomes) -> bool {
+    let (answers, errors): (Vec<_>, Vec<_>) = outcomes.iter().partition(|(_, a)| a.is_ok());
+    let some_fail = !errors.is_empty() && !answers.is_empty();
+    some_fail || !answers.iter().map(|(_, a)| a).all_equal()
+}
+
+/// An input that the implementations of a check disagree on.
+#[derive(Debug, Clone)]
+pub struct Disagreement {
+    pub day: u32,
+    pub check: Check,
+    pub seed: u64,
+    /// The generated input.
+    pub input: String,
+    /// The smallest input found that they still disagree on.
+    pub reproducer: String,
+    /// The outcomes on the reproducer.
+    pub outcomes: Outcomes,
+}
+
+impl Display for Disagreement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "day {}: {} disagree on the input of seed {}, minimized from {} to {} lines:",
+            self.day,
+            self.check,
+            self.seed,
+            self.input.lines().count(),
+            self.reproducer.lines().count()
+        )?;
+        for line in self.reproducer.lines() {
+            writeln!(f, "  | {line}")?;
+        }
+        for (name, answer) in &self.outcomes {
+            match answer {
+                Ok(answer) => writeln!(f, "{name}: {answer}")?,
+                Err(e) => writeln!(f, "{name}: error: {e}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The first check of `solution` that disagrees on `input`, with a minimized reproducer.
+pub fn check_input(solution: &dyn Solution, seed: u64, input: &str) -> Option<Disagreement> {
+    checks(solution).into_iter().find_map(|check| {
+        let outcomes = outcomes(solution, check, input)?;
+        disagree(&outcomes).then(|| shrink(solution, check, seed, input))
+    })
+}
+
+fn shrink(solution: &dyn Solution, check: Check, seed: u64, input: &str) -> Disagreement {
+    info!(day = solution.day(), %check, seed, "disagreement, minimizing");
+    let reproducer = minimize(input, |input| {
+        outcomes(solution, check, input).is_some_and(|o| disagree(&o))
+    });
+    let outcomes = outcomes(solution, check, &reproducer).unwrap_or_default();
+    Disagreement {
+        day: solution.day(),
+        check,
+        seed,
+        input: input.to_string(),
+        reproducer,
+        outcomes,
+    }
+}
+
+/// On how many inputs the implementations of a check could be compared.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Coverage {
+    /// Inputs on which at least two implementations gave an outcome.
+    pub compared: u64,
+    /// Inputs that didn't parse, or that all but one implementation couldn't handle.
+    pub skipped: u64,
+}
+
+/// What [`find`] found: the first disagreement, and the coverage of every check until then.
+#[derive(Debug, Clone)]
+pub struct Search {
+    pub disagreement: Option<Disagreement>,
+    pub coverage: Vec<(Check, Coverage)>,
+}
+
+/// Check `solution` on the inputs `generator` makes from each of `seeds`, stopping at the
+/// first disagreement. A check that was skipped on every input is an error, as it proved
+/// nothing.
+pub fn find(
+    solution: &dyn Solution,
+    generator: &Generator,
+    seeds: Range<u64>,
+    params: &[ParamValue],
+) -> Result<Search> {
+    let mut coverage = checks(solution)
+        .into_iter()
+        .map(|check| (check, Coverage::default()))
+        .collect::<Vec<_>>();
+    for seed in seeds {
+        if coverage.is_empty() {
+            break;
+        }
+        let input = generator.generate(seed, params)?;
+        for index in 0..coverage.len() {
+            let check = coverage[index].0;
+            match outcomes(solution, check, &input) {
+                Some(outcomes) if outcomes.len() > 1 => {
+                    coverage[index].1.compared += 1;
+                    if disagree(&outcomes) {
+                        let disagreement = Some(shrink(solution, check, seed, &input));
+                        return Ok(Search {
+                            disagreement,
+                            coverage,
+                        });
+                    }
+                }
+                _ => coverage[index].1.skipped += 1,
+            }
+        }
+        debug!(day = solution.day(), seed, "agreed");
+    }
+
+    if let Some((check, counts)) = coverage.iter().find(|(_, c)| c.compared == 0) {
+        Err(anyhow!(
+            "day {}: {check} couldn't be compared on any of the {} inputs",
+            solution.day(),
+            counts.skipped
+        ))?;
+    }
+    Ok(Search {
+        disagreement: None,
+        coverage,
+    })
+}
+
+/// Give up shrinking after this many tries, keeping the smallest input so far.
+const MAX_TRIES: usize = 10_000;
+
+/// Shrink `input` while `fails` holds: drop chunks of lines, then chunks of characters
+/// within lines, then make the numbers smaller, until none of it helps.
+fn minimize(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut tries = 0;
+    let mut fails = |lines: &[String]| {
+        tries += 1;
+        tries <= MAX_TRIES && fails(&join(lines))
+    };
+    let mut lines = input.lines().map(str::to_string).collect::<Vec<_>>();
+
+    loop {
+        let before = lines.clone();
+        lines = remove_chunks(lines, |lines| fails(lines));
+        for index in 0..lines.len() {
+            let chars = lines[index].chars().collect::<Vec<_>>();
+            let chars = remove_chunks(chars, |chars| {
+                let mut candidate = lines.clone();
+                candidate[index] = chars.iter().collect();
+                fails(&candidate)
+            });
+            lines[index] = chars.into_iter().collect();
+        }
+        for index in 0..lines.len() {
+            lines[index] = shrink_numbers(&lines[index], |line| {
+                let mut candidate = lines.clone();
+                candidate[index] = line.to_string();
+                fails(&candidate)
+            });
+        }
+        if lines == before {
+            break;
+        }
+    }
+    join(&lines)
+}
+
+fn join(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// Drop ever smaller chunks of `items` while what is left still fails.
+fn remove_chunks<T: Clone>(mut items: Vec<T>, mut fails: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut chunk = items.len().div_ceil(2);
+    while chunk > 0 {
+        let mut start = 0;
+        while start < items.len() {
+            let end = (start + chunk).min(items.len());
+            let candidate = [&items[..start], &items[end..]].concat();
+            if fails(&candidate) {
+                items = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk /= 2;
+    }
+    items
+}
+
+/// Replace each number in `line` with 0, 1 or half of it, for as long as that still fails.
+fn shrink_numbers(line: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    let digits_end = |line: &str, begin: usize| {
+        line[begin..]
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or(line.len(), |len| begin + len)
+    };
+    let mut line = line.to_string();
+    let mut start = 0;
+    while let Some(offset) = line[start..].find(|c: char| c.is_ascii_digit()) {
+        let begin = start + offset;
+        let mut end = digits_end(&line, begin);
+        while let Ok(n) = line[begin..end].parse::<u64>() {
+            let smaller = [0, 1, n / 2]
+                .into_iter()
+                .filter(|&m| m < n)
+                .map(|m| format!("{}{m}{}", &line[..begin], &line[end..]))
+                .find(|candidate| fails(candidate));
+            let Some(candidate) = smaller else { break };
+            line = candidate;
+            end = digits_end(&line, begin);
+        }
+        start = end;
+    }
+    line
+}
+
+#[cfg(test)]
+mod test {
+    use super::{checks, find, minimize, Check};
+    use crate::{
+        generate::{generator, generators, ParamValue},
+        parse::{number, ParseError},
+        registry,
+        solution::{Day, Part},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn registered_implementations_agree() -> Result<()> {
+        for generator in generators() {
+            let solution = registry()
+                .get(generator.day)
+                .expect("every generator has a day");
+            let params = match generator.day {
+                5 => vec!["moves=50".parse::<ParamValue>()?],
+                11 => vec!["monkeys=4".parse()?],
+                _ => vec![],
+            };
+            let search = find(solution, generator, 0..3, &params)?;
+            if let Some(disagreement) = search.disagreement {
+                panic!("{disagreement}");
+            }
+            for (check, coverage) in search.coverage {
+                assert_eq!(coverage.skipped, 0, "day {}: {check}", generator.day);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn days_register_checks() {
+        let day = |day| registry().get(day).expect("implemented");
+        assert_eq!(checks(day(6)), [Check::Variants(Part::One)]);
+        assert_eq!(checks(day(5)).len(), 1);
+        assert_eq!(checks(day(11)).len(), 1);
+        assert!(checks(day(1)).is_empty());
+    }
+
+    fn numbers(input: &str) -> Result<Vec<usize>, ParseError> {
+        input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| number(line, 0..line.len()))
+            .collect()
+    }
+
+    #[test]
+    fn disagreements_are_minimized() -> Result<()> {
+        let solution = Day::new(99, numbers)
+            .solver(Part::One, Some("Sum"), |n| Ok(n.iter().sum::<usize>()))
+            .solver(Part::One, Some("Buggy"), |n| {
+                Ok(n.iter()
+                    .map(|&n| if n > 9 { n + 1 } else { n })
+                    .sum::<usize>())
+            });
+        let disagreement =
+            super::check_input(&solution, 7, "3\n12\n5\n40\n").expect("the variants disagree");
+        assert_eq!(disagreement.check, Check::Variants(Part::One));
+        assert_eq!(disagreement.reproducer, "10\n");
+        assert_eq!(disagreement.outcomes.len(), 2);
+        assert!(super::check_input(&solution, 7, "3\n5\n").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn checks_that_never_compare_fail() {
+        let solution = Day::new(1, numbers).cross_check(
+            "never",
+            &[("A", |_| Ok(None::<usize>)), ("B", |n| Ok(Some(n.len())))],
+        );
+        let generator = generator(1).expect("day 1 has a generator");
+        let error = find(&solution, generator, 0..3, &[]).expect_err("nothing was compared");
+        assert_eq!(
+            error.to_string(),
+            "day 1: the \"never\" cross-check couldn't be compared on any of the 3 inputs"
+        );
+    }
+
+    #[test]
+    fn minimize_lines_and_characters() {
+        let fails = |input: &str| input.contains('x') && input.lines().any(|l| l.contains('y'));
+        assert_eq!(minimize("a\nbxc\nd\ney\n", fails), "x\ny\n");
+        assert_eq!(minimize("ab xy 300\n", |s| s.contains("xy")), "xy\n");
+        assert_eq!(minimize("n 300\n", |s| s.contains("n 1")), "n 1\n");
+    }
+}
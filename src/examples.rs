@@ -0,0 +1,162 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::{
+    manifest::{check, parse_answer, parse_part, Check},
+    solution::{Answer, Part},
+};
+
+/// An example input, `dayN.example.txt` or `dayN.exampleK.txt`, with the expected answers
+/// from the sidecar file next to it (`dayN.example.expected`, `dayN.exampleK.expected`):
+///
+/// ```toml
+/// part1 = 13
+/// part2 = 1
+/// long_running = ["part2"]
+/// ```
+///
+/// Parts without an expected answer are not checked. Parts listed in `long_running` are
+/// only checked with the `long-running-tests` feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub day: u32,
+    pub name: String,
+    pub input_path: PathBuf,
+    pub expected_path: PathBuf,
+    pub expected: BTreeMap<Part, String>,
+    pub long_running: Vec<Part>,
+}
+
+impl Example {
+    pub fn check(&self, part: Part, answer: &Answer) -> Check {
+        check(self.expected.get(&part).map(|s| s.as_str()), answer)
+    }
+}
+
+/// Split `day9.example2.txt` into `(9, "example2")`.
+fn parse_file_name(name: &str) -> Option<(u32, &str)> {
+    let (day, rest) = name.strip_prefix("day")?.split_once('.')?;
+    let example = rest.strip_suffix(".txt")?;
+    let number = example.strip_prefix("example")?;
+    if !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((day.parse().ok()?, example))
+}
+
+fn parse_expected(text: &str) -> Result<(BTreeMap<Part, String>, Vec<Part>)> {
+    let table: toml::Table = text.parse()?;
+
+    let mut expected = BTreeMap::new();
+    let mut long_running = vec![];
+    for (key, value) in table {
+        if key == "long_running" {
+            let toml::Value::Array(parts) = value else {
+                Err(anyhow!("long_running is not an array"))?
+            };
+            for part in parts {
+                let toml::Value::String(part) = part else {
+                    Err(anyhow!("long_running should list parts like \"part2\""))?
+                };
+                long_running.push(parse_part(&part)?);
+            }
+        } else {
+            let part = parse_part(&key)?;
+            let answer = parse_answer(value).with_context(|| format!("bad answer for {key}"))?;
+            expected.insert(part, answer);
+        }
+    }
+
+    Ok((expected, long_running))
+}
+
+/// Find every example input in `dir`, the [`input_dir`](crate::runner::input_dir) of a year, sorted by day and name.
+///
+/// Fails if an example has no sidecar file, so a new example can't go unchecked by accident.
+pub fn discover(dir: impl AsRef<Path>) -> Result<Vec<Example>> {
+    let dir = dir.as_ref();
+    let mut examples = vec![];
+    for entry in std::fs::read_dir(dir).with_context(|| format!("could not read {dir:?}"))? {
+        let input_path = entry?.path();
+        let Some((day, name)) = input_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(parse_file_name)
+        else {
+            continue;
+        };
+
+        let expected_path = input_path.with_extension("expected");
+        let text = std::fs::read_to_string(&expected_path)
+            .with_context(|| format!("no expected answers for {input_path:?}"))?;
+        let (expected, long_running) = parse_expected(&text)
+            .with_context(|| format!("bad expected answers {expected_path:?}"))?;
+
+        examples.push(Example {
+            day,
+            name: name.to_string(),
+            input_path,
+            expected_path,
+            expected,
+            long_running,
+        });
+    }
+    examples.sort_by(|a, b| (a.day, &a.name).cmp(&(b.day, &b.name)));
+    Ok(examples)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{discover, parse_expected, parse_file_name};
+    use crate::runner::input_dir;
+    use crate::solution::Part;
+    use anyhow::Result;
+
+    #[test]
+    fn file_names() {
+        assert_eq!(parse_file_name("day9.example.txt"), Some((9, "example")));
+        assert_eq!(parse_file_name("day9.example2.txt"), Some((9, "example2")));
+        assert_eq!(parse_file_name("day9.txt"), None);
+        assert_eq!(parse_file_name("day9.example.expected"), None);
+        assert_eq!(parse_file_name("day9.examples.txt"), None);
+        assert_eq!(parse_file_name("dayX.example.txt"), None);
+    }
+
+    #[test]
+    fn expected_answers() -> Result<()> {
+        let (expected, long_running) = parse_expected(
+            "part1 = 13\npart2 = \"\"\"\n#.\n.#\n\"\"\"\nlong_running = [\"part2\"]",
+        )?;
+        assert_eq!(expected.get(&Part::One).map(|s| s.as_str()), Some("13"));
+        assert_eq!(expected.get(&Part::Two).map(|s| s.as_str()), Some("#.\n.#"));
+        assert_eq!(long_running, vec![Part::Two]);
+
+        assert!(parse_expected("part3 = 1").is_err());
+        assert!(parse_expected("long_running = \"part2\"").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_sidecar_is_an_error() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("day3.example.txt"), "")?;
+        assert!(discover(dir.path()).is_err());
+
+        std::fs::write(dir.path().join("day3.example.expected"), "part2 = 4")?;
+        let examples = discover(dir.path())?;
+        assert_eq!(examples.len(), 1);
+        assert_eq!(examples[0].day, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn every_repository_example_is_found() -> Result<()> {
+        let examples = discover(input_dir(2022))?;
+        assert!(examples.iter().any(|e| e.day == 9 && e.name == "example2"));
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use super::{Generator, Param, Params, Rng};
+
+pub(super) const GENERATOR: Generator = Generator {
+    day: 1,
+    params: &[
+        Param {
+            name: "elves",
+            default: 250,
+            help: "number of elves",
+        },
+        Param {
+            name: "max_items",
+            default: 15,
+            help: "most food items carried by one elf",
+        },
+        Param {
+            name: "max_calories",
+            default: 70000,
+            help: "most calories in one item",
+        },
+    ],
+    generate,
+};
+
+fn generate(rng: &mut Rng, params: &Params) -> Result<String> {
+    let max_items = params.get("max_items");
+    let max_calories = params.get("max_calories");
+    if max_items == 0 || max_calories == 0 {
+        Err(anyhow!("max_items and max_calories must be at least 1"))?;
+    }
+
+    let mut out = String::new();
+    for elf in 0..params.get("elves") {
+        if elf > 0 {
+            writeln!(out)?;
+        }
+        for _ in 0..rng.range(1..=max_items) {
+            writeln!(out, "{}", rng.range(1..=max_calories))?;
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,41 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use super::{Generator, Param, Params, Rng};
+
+pub(super) const GENERATOR: Generator = Generator {
+    day: 10,
+    params: &[
+        Param {
+            name: "instructions",
+            default: 146,
+            help: "length of the program",
+        },
+        Param {
+            name: "noop_percent",
+            default: 30,
+            help: "chance of a `noop`",
+        },
+        Param {
+            name: "max_addx",
+            default: 20,
+            help: "largest change of `x` in one `addx`",
+        },
+    ],
+    generate,
+};
+
+fn generate(rng: &mut Rng, params: &Params) -> Result<String> {
+    let max_addx = params.get("max_addx") as i64;
+    let mut out = String::new();
+    for _ in 0..params.get("instructions") {
+        if rng.chance(params.get("noop_percent")) {
+            writeln!(out, "noop")?;
+        } else {
+            let dx = rng.range(0..=2 * max_addx as u64) as i64 - max_addx;
+            writeln!(out, "addx {dx}")?;
+        }
+    }
+    Ok(out)
+}
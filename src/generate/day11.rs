@@ -0,0 +1,156 @@
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use super::{Generator, Param, Params, Rng};
+
+pub(super) const GENERATOR: Generator = Generator {
+    day: 11,
+    params: &[
+        Param {
+            name: "monkeys",
+            default: 8,
+            help: "number of monkeys, at least 3",
+        },
+        Param {
+            name: "max_items",
+            default: 8,
+            help: "most items a monkey starts with",
+        },
+        Param {
+            name: "squares",
+            default: 1,
+            help: "monkeys whose operation is `old * old`",
+        },
+    ],
+    generate,
+};
+
+/// Give up if no layout keeps its worry levels in an `i64` after this many tries.
+const ATTEMPTS: usize = 1000;
+
+/// The rounds of part 1, and of the cross-check that plays them without relief.
+const ROUNDS: usize = 20;
+
+enum Op {
+    Add(i64),
+    Multiply(i64),
+    Square,
+}
+
+struct Monkey {
+    items: Vec<i64>,
+    op: Op,
+    divisor: i64,
+    targets: [usize; 2],
+}
+
+fn generate(rng: &mut Rng, params: &Params) -> Result<String> {
+    let count = params.get_usize("monkeys");
+    if count < 3 {
+        Err(anyhow!("need at least 3 monkeys"))?;
+    }
+    if params.get_usize("squares") > count {
+        Err(anyhow!("can't have more squares than monkeys"))?;
+    }
+
+    for _ in 0..ATTEMPTS {
+        let monkeys = layout(rng, params, count);
+        if fits(&monkeys, true) && fits(&monkeys, false) {
+            return write(&monkeys);
+        }
+    }
+    Err(anyhow!(
+        "every layout overflowed within {ROUNDS} rounds, try fewer squares"
+    ))
+}
+
+fn layout(rng: &mut Rng, params: &Params, count: usize) -> Vec<Monkey> {
+    let mut divisors = primes(count);
+    rng.shuffle(&mut divisors);
+    let mut squares = vec![false; count];
+    squares[..params.get_usize("squares")].fill(true);
+    rng.shuffle(&mut squares);
+
+    (0..count)
+        .map(|index| {
+            let op = match squares[index] {
+                true => Op::Square,
+                false if rng.chance(50) => Op::Add(rng.range(1..=9) as i64),
+                false => Op::Multiply(rng.range(2..=19) as i64),
+            };
+            let if_true = (index + 1 + rng.below(count - 1)) % count;
+            let mut if_false = if_true;
+            while if_false == if_true || if_false == index {
+                if_false = rng.below(count);
+            }
+            Monkey {
+                items: (0..rng.range(1..=params.get("max_items").max(1)))
+                    .map(|_| rng.range(50..=99) as i64)
+                    .collect(),
+                op,
+                divisor: divisors[index],
+                targets: [if_true, if_false],
+            }
+        })
+        .collect()
+}
+
+/// The first `count` primes, so every test divisor is different.
+fn primes(count: usize) -> Vec<i64> {
+    let mut primes: Vec<i64> = vec![];
+    let mut n = 2;
+    while primes.len() < count {
+        if primes.iter().all(|p| n % p != 0) {
+            primes.push(n);
+        }
+        n += 1;
+    }
+    primes
+}
+
+/// Play [`ROUNDS`] rounds without wrapping worry levels, dividing them by 3 if `relief` is
+/// on like part 1 does, to check they stay in an `i64`. Then day 11's answers with and
+/// without relief can be compared with the remainders of part 2.
+fn fits(monkeys: &[Monkey], relief: bool) -> bool {
+    let mut items = monkeys.iter().map(|m| m.items.clone()).collect::<Vec<_>>();
+    for _ in 0..ROUNDS {
+        for (index, monkey) in monkeys.iter().enumerate() {
+            for old in std::mem::take(&mut items[index]) {
+                let new = match monkey.op {
+                    Op::Add(n) => old.checked_add(n),
+                    Op::Multiply(n) => old.checked_mul(n),
+                    Op::Square => old.checked_mul(old),
+                };
+                let Some(new) = new.map(|n| if relief { n / 3 } else { n }) else {
+                    return false;
+                };
+                let target = monkey.targets[usize::from(new % monkey.divisor != 0)];
+                items[target].push(new);
+            }
+        }
+    }
+    true
+}
+
+fn write(monkeys: &[Monkey]) -> Result<String> {
+    let mut out = String::new();
+    for (index, monkey) in monkeys.iter().enumerate() {
+        if index > 0 {
+            writeln!(out)?;
+        }
+        let items = monkey.items.iter().map(i64::to_string).collect::<Vec<_>>();
+        let op = match monkey.op {
+            Op::Add(n) => format!("old + {n}"),
+            Op::Multiply(n) => format!("old * {n}"),
+            Op::Square => "old * old".to_string(),
+        };
+        writeln!(out, "Monkey {index}:")?;
+        writeln!(out, "  Starting items: {}", items.join(", "))?;
+        writeln!(out, "  Operation: new = {op}")?;
+        writeln!(out, "  Test: divisible by {}", monkey.divisor)?;
+        writeln!(out, "    If true: throw to monkey {}", monkey.targets[0])?;
+        writeln!(out, "    If false: throw to monkey {}", monkey.targets[1])?;
+    }
+    Ok(out)
+}
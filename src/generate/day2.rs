@@ -0,0 +1,25 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use super::{Generator, Param, Params, Rng};
+
+pub(super) const GENERATOR: Generator = Generator {
+    day: 2,
+    params: &[Param {
+        name: "rounds",
+        default: 2500,
+        help: "number of rounds in the strategy guide",
+    }],
+    generate,
+};
+
+fn generate(rng: &mut Rng, params: &Params) -> Result<String> {
+    let mut out = String::new();
+    for _ in 0..params.get("rounds") {
+        let left = rng.pick(&['A', 'B', 'C']);
+        let right = rng.pick(&['X', 'Y', 'Z']);
+        writeln!(out, "{left} {right}")?;
+    }
+    Ok(out)
+}
@@ -0,0 +1,58 @@
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use super::{Generator, Param, Params, Rng};
+
+pub(super) const GENERATOR: Generator = Generator {
+    day: 3,
+    params: &[
+        Param {
+            name: "groups",
+            default: 100,
+            help: "number of groups of three elves",
+        },
+        Param {
+            name: "max_compartment",
+            default: 24,
+            help: "most items in one compartment (at least 2)",
+        },
+    ],
+    generate,
+};
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Every rucksack has exactly one item type in both compartments, and every group of
+/// three has exactly one item type (the badge) in common.
+fn generate(rng: &mut Rng, params: &Params) -> Result<String> {
+    let max_compartment = params.get("max_compartment");
+    if max_compartment < 2 {
+        Err(anyhow!("max_compartment must be at least 2"))?;
+    }
+
+    let mut out = String::new();
+    for _ in 0..params.get("groups") {
+        let mut items = ITEMS.to_vec();
+        rng.shuffle(&mut items);
+        let badge = items[0];
+
+        // Each elf of the group gets its own 17 item types, so only the badge is shared.
+        for own in items[1..].chunks(17) {
+            let shared = own[0];
+            let (left_only, right_only) = own[1..].split_at(8);
+            let size = rng.range(2..=max_compartment) as usize;
+
+            let mut left = vec![shared, badge];
+            left.extend((2..size).map(|_| *rng.pick(left_only)));
+            let mut right = vec![shared];
+            right.extend((1..size).map(|_| *rng.pick(right_only)));
+            rng.shuffle(&mut left);
+            rng.shuffle(&mut right);
+
+            left.extend(right);
+            writeln!(out, "{}", String::from_utf8_lossy(&left))?;
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,43 @@
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use super::{Generator, Param, Params, Rng};
+
+pub(super) const GENERATOR: Generator = Generator {
+    day: 4,
+    params: &[
+        Param {
+            name: "pairs",
+            default: 1000,
+            help: "number of pairs of elves",
+        },
+        Param {
+            name: "sections",
+            default: 99,
+            help: "number of the last section",
+        },
+    ],
+    generate,
+};
+
+fn generate(rng: &mut Rng, params: &Params) -> Result<String> {
+    let sections = params.get("sections");
+    if sections == 0 {
+        Err(anyhow!("sections must be at least 1"))?;
+    }
+
+    let mut out = String::new();
+    for _ in 0..params.get("pairs") {
+        let first = range(rng, sections);
+        let second = range(rng, sections);
+        writeln!(out, "{first},{second}")?;
+    }
+    Ok(out)
+}
+
+fn range(rng: &mut Rng, sections: u64) -> String {
+    let low = rng.range(1..=sections);
+    let high = rng.range(low..=sections);
+    format!("{low}-{high}")
+}
@@ -0,0 +1,80 @@
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use super::{Generator, Param, Params, Rng};
+
+pub(super) const GENERATOR: Generator = Generator {
+    day: 5,
+    params: &[
+        Param {
+            name: "stacks",
+            default: 9,
+            help: "number of stacks (1 to 9)",
+        },
+        Param {
+            name: "height",
+            default: 8,
+            help: "highest stack at the start",
+        },
+        Param {
+            name: "moves",
+            default: 500,
+            help: "number of rearrangement steps",
+        },
+    ],
+    generate,
+};
+
+/// Every move takes crates from a stack that has that many.
+fn generate(rng: &mut Rng, params: &Params) -> Result<String> {
+    let count = params.get_usize("stacks");
+    let height = params.get_usize("height");
+    if !(1..=9).contains(&count) {
+        Err(anyhow!("stacks must be 1 to 9, so the labels line up"))?;
+    }
+    if height == 0 {
+        Err(anyhow!("height must be at least 1"))?;
+    }
+
+    let mut stacks = (0..count)
+        .map(|_| {
+            let crates = rng.range(0..=height as u64) as usize;
+            (0..crates)
+                .map(|_| rng.letter().to_ascii_uppercase())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    if stacks.iter().all(Vec::is_empty) {
+        stacks[0].push('A');
+    }
+
+    let mut out = String::new();
+    let top = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    for row in (0..top).rev() {
+        let line = stacks
+            .iter()
+            .map(|stack| stack.get(row).map_or("   ".into(), |c| format!("[{c}]")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(out, "{line}")?;
+    }
+    let labels = (1..=count).map(|n| format!(" {n} ")).collect::<Vec<_>>();
+    writeln!(out, "{}\n", labels.join(" "))?;
+
+    // Only the sizes matter for which moves are possible.
+    let mut sizes = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+    for _ in 0..params.get("moves") {
+        let non_empty = (0..count).filter(|&i| sizes[i] > 0).collect::<Vec<_>>();
+        let from = *rng.pick(&non_empty);
+        let to = match count {
+            1 => from,
+            _ => (from + 1 + rng.below(count - 1)) % count,
+        };
+        let crates = rng.range(1..=sizes[from] as u64) as usize;
+        sizes[from] -= crates;
+        sizes[to] += crates;
+        writeln!(out, "move {crates} from {} to {}", from + 1, to + 1)?;
+    }
+    Ok(out)
+}
@@ -0,0 +1,83 @@
+use anyhow::{anyhow, Result};
+
+use super::{Generator, Param, Params, Rng};
+
+pub(super) const GENERATOR: Generator = Generator {
+    day: 6,
+    params: &[
+        Param {
+            name: "length",
+            default: 4096,
+            help: "length of the datastream",
+        },
+        Param {
+            name: "packet",
+            default: 1000,
+            help: "characters read when the start-of-packet marker is found (part 1)",
+        },
+        Param {
+            name: "message",
+            default: 3000,
+            help: "characters read when the start-of-message marker is found (part 2)",
+        },
+    ],
+    generate,
+};
+
+/// A datastream whose markers are found after exactly `packet` and `message` characters.
+///
+/// Before the packet marker only three letters are used, and before the message marker
+/// only thirteen, so no earlier window can be a marker. The character before each marker
+/// is repeated as its first character, so the windows overlapping a marker aren't either.
+fn generate(rng: &mut Rng, params: &Params) -> Result<String> {
+    let length = params.get_usize("length");
+    let packet = params.get_usize("packet");
+    let message = params.get_usize("message");
+    if packet < 4 || message < packet + 15 || length < message {
+        Err(anyhow!(
+            "need 4 <= packet, packet + 15 <= message and message <= length"
+        ))?;
+    }
+
+    let mut alphabet = (b'a'..=b'z').collect::<Vec<_>>();
+    rng.shuffle(&mut alphabet);
+    let (few, rest) = alphabet.split_at(3);
+
+    let mut stream = (0..packet - 4).map(|_| *rng.pick(few)).collect::<Vec<_>>();
+    push_marker(rng, &mut stream, 4, &alphabet);
+
+    // The packet marker may use any letters, so they come first among the thirteen.
+    let mut thirteen = few.to_vec();
+    for &c in stream[packet - 4..].iter().chain(rest) {
+        if thirteen.len() < 13 && !thirteen.contains(&c) {
+            thirteen.push(c);
+        }
+    }
+    while stream.len() < message - 14 {
+        stream.push(*rng.pick(&thirteen));
+    }
+    push_marker(rng, &mut stream, 14, &alphabet);
+
+    while stream.len() < length {
+        stream.push(rng.letter() as u8);
+    }
+    let mut out = String::from_utf8_lossy(&stream).into_owned();
+    out.push('\n');
+    Ok(out)
+}
+
+/// Append `size` different letters, starting with the last letter of `stream`.
+fn push_marker(rng: &mut Rng, stream: &mut Vec<u8>, size: usize, alphabet: &[u8]) {
+    let first = match stream.last() {
+        Some(&last) => last,
+        None => *rng.pick(alphabet),
+    };
+    let mut others = alphabet
+        .iter()
+        .copied()
+        .filter(|&c| c != first)
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut others);
+    stream.push(first);
+    stream.extend(&others[..size - 1]);
+}
@@ -0,0 +1,154 @@
+use std::{collections::HashSet, fmt::Write, ops::RangeInclusive};
+
+use anyhow::{anyhow, Result};
+
+use super::{Generator, Param, Params, Rng};
+
+pub(super) const GENERATOR: Generator = Generator {
+    day: 7,
+    params: &[
+        Param {
+            name: "dirs",
+            default: 200,
+            help: "number of directories besides `/`",
+        },
+        Param {
+            name: "depth",
+            default: 10,
+            help: "deepest nesting of directories",
+        },
+        Param {
+            name: "max_files",
+            default: 10,
+            help: "most files in one directory",
+        },
+        Param {
+            name: "total_size",
+            default: 50_000_000,
+            help: "size of all files (part 2 needs 40000000 to 70000000)",
+        },
+    ],
+    generate,
+};
+
+/// The sizes of all files for which part 2 has to free up space, and still can.
+const TOTAL_SIZES: RangeInclusive<u64> = 40_000_000..=70_000_000;
+
+struct Dir {
+    name: String,
+    depth: u64,
+    dirs: Vec<usize>,
+    files: Vec<(String, u64)>,
+}
+
+/// A transcript that lists every directory before entering it, depth first.
+fn generate(rng: &mut Rng, params: &Params) -> Result<String> {
+    let depth = params.get("depth");
+    let max_files = params.get("max_files");
+    if depth == 0 && params.get("dirs") > 0 {
+        Err(anyhow!("depth must be at least 1 to have any directories"))?;
+    }
+    let total_size = params.get("total_size");
+    if !TOTAL_SIZES.contains(&total_size) {
+        Err(anyhow!("total_size must be from 40000000 to 70000000"))?;
+    }
+
+    let mut dirs = vec![Dir {
+        name: "/".to_string(),
+        depth: 0,
+        dirs: vec![],
+        files: vec![],
+    }];
+    let mut shallow = vec![0];
+    for _ in 0..params.get("dirs") {
+        // Grow one long branch now and then, so the deepest level is reached.
+        let parent = match rng.chance(20) {
+            true => *shallow.last().unwrap_or(&0),
+            false => *rng.pick(&shallow),
+        };
+        let id = dirs.len();
+        let name = unique_name(rng, &dirs, parent, "");
+        dirs[parent].dirs.push(id);
+        dirs.push(Dir {
+            name,
+            depth: dirs[parent].depth + 1,
+            dirs: vec![],
+            files: vec![],
+        });
+        if dirs[id].depth < depth {
+            shallow.push(id);
+        }
+    }
+
+    // Spread the total size over the files in proportion to random weights.
+    let mut weights = vec![];
+    for id in 0..dirs.len() {
+        for _ in 0..rng.range(0..=max_files) {
+            let extension = match rng.chance(50) {
+                true => format!(".{}", word(rng, 3)),
+                false => String::new(),
+            };
+            let name = unique_name(rng, &dirs, id, &extension);
+            dirs[id].files.push((name, 0));
+            weights.push((id, dirs[id].files.len() - 1, rng.range(1..=1000)));
+        }
+    }
+    let total_weight = weights.iter().map(|&(_, _, w)| w).sum::<u64>().max(1);
+    for (id, file, weight) in weights {
+        dirs[id].files[file].1 =
+            (total_size as u128 * weight as u128 / total_weight as u128).max(1) as u64;
+    }
+
+    let mut out = String::new();
+    // Work through the tree without recursion, as it may be very deep.
+    let mut todo = vec![Some(0)];
+    while let Some(step) = todo.pop() {
+        let Some(id) = step else {
+            writeln!(out, "$ cd ..")?;
+            continue;
+        };
+        let dir = &dirs[id];
+        writeln!(out, "$ cd {}", dir.name)?;
+        writeln!(out, "$ ls")?;
+        let mut entries = dir
+            .dirs
+            .iter()
+            .map(|&d| format!("dir {}", dirs[d].name))
+            .chain(
+                dir.files
+                    .iter()
+                    .map(|(name, size)| format!("{size} {name}")),
+            )
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut entries);
+        for entry in entries {
+            writeln!(out, "{entry}")?;
+        }
+        for &child in dir.dirs.iter().rev() {
+            todo.push(None);
+            todo.push(Some(child));
+        }
+    }
+    Ok(out)
+}
+
+fn word(rng: &mut Rng, max_len: u64) -> String {
+    (0..rng.range(1..=max_len)).map(|_| rng.letter()).collect()
+}
+
+/// A name that isn't used in directory `id` yet.
+fn unique_name(rng: &mut Rng, dirs: &[Dir], id: usize, extension: &str) -> String {
+    let dir = &dirs[id];
+    let taken = dir
+        .dirs
+        .iter()
+        .map(|&d| dirs[d].name.as_str())
+        .chain(dir.files.iter().map(|(name, _)| name.as_str()))
+        .collect::<HashSet<_>>();
+    loop {
+        let name = format!("{}{extension}", word(rng, 8));
+        if !taken.contains(name.as_str()) {
+            return name;
+        }
+    }
+}
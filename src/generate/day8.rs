@@ -0,0 +1,35 @@
+use anyhow::{anyhow, Result};
+
+use super::{Generator, Param, Params, Rng};
+
+pub(super) const GENERATOR: Generator = Generator {
+    day: 8,
+    params: &[
+        Param {
+            name: "width",
+            default: 99,
+            help: "trees per row",
+        },
+        Param {
+            name: "height",
+            default: 99,
+            help: "number of rows",
+        },
+    ],
+    generate,
+};
+
+fn generate(rng: &mut Rng, params: &Params) -> Result<String> {
+    let width = params.get_usize("width");
+    let height = params.get_usize("height");
+    if width == 0 || height == 0 {
+        Err(anyhow!("width and height must be at least 1"))?;
+    }
+
+    let mut out = String::with_capacity((width + 1) * height);
+    for _ in 0..height {
+        out.extend((0..width).map(|_| (b'0' + rng.below(10) as u8) as char));
+        out.push('\n');
+    }
+    Ok(out)
+}
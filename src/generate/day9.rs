@@ -0,0 +1,36 @@
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use super::{Generator, Param, Params, Rng};
+
+pub(super) const GENERATOR: Generator = Generator {
+    day: 9,
+    params: &[
+        Param {
+            name: "moves",
+            default: 2000,
+            help: "number of head motions",
+        },
+        Param {
+            name: "max_steps",
+            default: 20,
+            help: "most steps in one motion",
+        },
+    ],
+    generate,
+};
+
+fn generate(rng: &mut Rng, params: &Params) -> Result<String> {
+    let max_steps = params.get("max_steps");
+    if max_steps == 0 {
+        Err(anyhow!("max_steps must be at least 1"))?;
+    }
+
+    let mut out = String::new();
+    for _ in 0..params.get("moves") {
+        let direction = rng.pick(&['U', 'D', 'L', 'R']);
+        writeln!(out, "{direction} {}", rng.range(1..=max_steps))?;
+    }
+    Ok(out)
+}
@@ -0,0 +1,290 @@
+//! Seeded generators of valid puzzle inputs, for testing the solvers on inputs of any size.
+//! They are for the days of [`DEFAULT_YEAR`](crate::DEFAULT_YEAR).
+//!
+//! The same seed and parameters always give the same input.
+
+use std::{collections::BTreeMap, ops::RangeInclusive, str::FromStr};
+
+use anyhow::{anyhow, Result};
+
+mod day1;
+mod day10;
+mod day11;
+mod day2;
+mod day3;
+mod day4;
+mod day5;
+mod day6;
+mod day7;
+mod day8;
+mod day9;
+
+/// A small, fast random number generator (SplitMix64), so inputs don't change with a
+/// dependency update.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (low, high) = range.into_inner();
+        match (high - low).checked_add(1) {
+            Some(count) => low + self.next_u64() % count,
+            None => self.next_u64(),
+        }
+    }
+
+    /// An index below `n`, which must not be 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        self.range(0..=n as u64 - 1) as usize
+    }
+
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.range(1..=100) <= percent
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    pub fn letter(&mut self) -> char {
+        (b'a' + self.below(26) as u8) as char
+    }
+}
+
+/// A tunable number, like the width of a forest.
+pub struct Param {
+    pub name: &'static str,
+    pub default: u64,
+    pub help: &'static str,
+}
+
+/// The value of every parameter of a generator.
+pub struct Params(BTreeMap<&'static str, u64>);
+
+impl Params {
+    /// The value of a parameter, which the generator must have declared.
+    pub fn get(&self, name: &str) -> u64 {
+        self.0[name]
+    }
+
+    pub fn get_usize(&self, name: &str) -> usize {
+        self.get(name) as usize
+    }
+}
+
+/// A `name=value` parameter from the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamValue {
+    pub name: String,
+    pub value: u64,
+}
+
+impl FromStr for ParamValue {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow!("expected `name=value`, got {s:?}"))?;
+        let value = value
+            .trim()
+            .replace('_', "")
+            .parse()
+            .map_err(|_| anyhow!("{name}: expected a number, got {value:?}"))?;
+        Ok(Self {
+            name: name.trim().to_string(),
+            value,
+        })
+    }
+}
+
+/// Makes inputs for one day.
+pub struct Generator {
+    pub day: u32,
+    pub params: &'static [Param],
+    generate: fn(&mut Rng, &Params) -> Result<String>,
+}
+
+impl Generator {
+    /// Generate an input, with the defaults for any parameter that isn't given.
+    pub fn generate(&self, seed: u64, values: &[ParamValue]) -> Result<String> {
+        let mut params = self
+            .params
+            .iter()
+            .map(|p| (p.name, p.default))
+            .collect::<BTreeMap<_, _>>();
+        for ParamValue { name, value } in values {
+            let slot = params.get_mut(name.as_str()).ok_or_else(|| {
+                let known = self.params.iter().map(|p| p.name).collect::<Vec<_>>();
+                anyhow!(
+                    "day {} has no parameter `{name}` (it has {})",
+                    self.day,
+                    known.join(", ")
+                )
+            })?;
+            *slot = *value;
+        }
+        (self.generate)(&mut Rng::new(seed), &Params(params))
+    }
+}
+
+static GENERATORS: [Generator; 11] = [
+    day1::GENERATOR,
+    day2::GENERATOR,
+    day3::GENERATOR,
+    day4::GENERATOR,
+    day5::GENERATOR,
+    day6::GENERATOR,
+    day7::GENERATOR,
+    day8::GENERATOR,
+    day9::GENERATOR,
+    day10::GENERATOR,
+    day11::GENERATOR,
+];
+
+pub fn generators() -> &'static [Generator] {
+    &GENERATORS
+}
+
+pub fn generator(day: u32) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|g| g.day == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{generator, generators, ParamValue};
+    use crate::{registry, Answer, Part};
+    use anyhow::Result;
+
+    fn values(values: &[&str]) -> Result<Vec<ParamValue>> {
+        values.iter().map(|v| v.parse()).collect()
+    }
+
+    /// Keep the slow days quick in debug builds.
+    fn small(day: u32) -> Result<Vec<ParamValue>> {
+        match day {
+            8 => values(&["width=40", "height=30"]),
+            11 => values(&["monkeys=5"]),
+            _ => Ok(vec![]),
+        }
+    }
+
+    #[test]
+    fn generated_inputs_are_solved() -> Result<()> {
+        for generator in generators() {
+            let day = generator.day;
+            let solution = registry().get(day).expect("every generator has a day");
+            for seed in 0..3 {
+                let input = generator.generate(seed, &small(day)?)?;
+                let data = solution.parse(&input)?;
+                for variant in solution.variants() {
+                    if day == 11
+                        && variant.part == Part::Two
+                        && !cfg!(feature = "long-running-tests")
+                    {
+                        continue;
+                    }
+                    solution.solve(&data, variant)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn streamed_answers_match() -> Result<()> {
+        for generator in generators() {
+            let day = generator.day;
+            let solution = registry().get(day).expect("every generator has a day");
+            for part in solution.stream_parts() {
+                for seed in 0..3 {
+                    let input = generator.generate(seed, &small(day)?)?;
+                    let windows = format!("\u{feff}{}\r\n", input.replace('\n', "\r\n"));
+                    let expected = solution.run(&input, part)?;
+                    for input in [&input, &windows] {
+                        let streamed = solution.solve_stream(&mut input.as_bytes(), part)?;
+                        assert_eq!(streamed, expected, "day {day} part {part}, seed {seed}");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn streamed_errors_match() -> Result<()> {
+        let solution = registry().get(10).expect("day 10 is implemented");
+        let input = "noop\naddx 3\n\naddx x\n";
+        let error = solution.part1(input).unwrap_err();
+        let streamed = solution
+            .solve_stream(&mut input.as_bytes(), Part::One)
+            .unwrap_err();
+        assert_eq!(streamed.to_string(), error.to_string());
+        assert!(error.to_string().starts_with("day 10, line 3, column 1"));
+        Ok(())
+    }
+
+    #[test]
+    fn same_seed_same_input() -> Result<()> {
+        let generator = generator(9).expect("day 9 has a generator");
+        assert_eq!(generator.generate(7, &[])?, generator.generate(7, &[])?);
+        assert_ne!(generator.generate(7, &[])?, generator.generate(8, &[])?);
+        Ok(())
+    }
+
+    #[test]
+    fn day6_markers_are_where_asked() -> Result<()> {
+        let generator = generator(6).expect("day 6 has a generator");
+        let solution = registry().get(6).expect("day 6 is implemented");
+        for seed in 0..20 {
+            let input =
+                generator.generate(seed, &values(&["length=600", "packet=30", "message=200"])?)?;
+            assert_eq!(solution.part1(&input)?, Answer::Number(30));
+            assert_eq!(solution.part2(&input)?, Answer::Number(200));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_parameters_are_rejected() -> Result<()> {
+        let generator = generator(8).expect("day 8 has a generator");
+        assert!(generator.generate(0, &values(&["depth=3"])?).is_err());
+        assert!("width".parse::<ParamValue>().is_err());
+        assert!("width=x".parse::<ParamValue>().is_err());
+        assert_eq!(
+            "width=5_000".parse::<ParamValue>().ok().map(|p| p.value),
+            Some(5000)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn day7_sizes_must_fit_the_disk() -> Result<()> {
+        let generator = generator(7).expect("day 7 has a generator");
+        assert!(generator
+            .generate(0, &values(&["total_size=80000000"])?)
+            .is_err());
+        assert!(generator
+            .generate(0, &values(&["total_size=70000000", "dirs=20"])?)
+            .is_ok());
+        Ok(())
+    }
+}
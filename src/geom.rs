@@ -0,0 +1,336 @@
+//! Integer points and vectors in 2D and 3D, with the arithmetic between them.
+//!
+//! A position minus a position is a delta, and a position plus a delta is a position. In 2D,
+//! `y` points up, like the rope of day 9.
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct PosXY {
+    pub x: isize,
+    pub y: isize,
+}
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct DeltaXY {
+    pub x: isize,
+    pub y: isize,
+}
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct PosXYZ {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct DeltaXYZ {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+/// The arithmetic shared by the 2D and 3D types, component by component.
+macro_rules! impl_vector_ops {
+    ($pos:ident, $delta:ident, $($c:ident),+) => {
+        impl $pos {
+            pub fn new($($c: isize),+) -> Self {
+                Self { $($c),+ }
+            }
+
+            /// The points from here to `end`, both included, a step for each unit of the
+            /// largest difference of a component. Straight and diagonal lines are exact;
+            /// others are rounded to the nearest points.
+            pub fn line_to(self, end: Self) -> impl Iterator<Item = Self> {
+                let delta = end - self;
+                let steps = delta.max_norm() as isize;
+                (0..=steps).map(move |i| {
+                    Self::new($(self.$c + round_fraction(delta.$c, i, steps)),+)
+                })
+            }
+        }
+
+        impl $delta {
+            pub fn new($($c: isize),+) -> Self {
+                Self { $($c),+ }
+            }
+
+            pub fn manhattan_norm(&self) -> usize {
+                0 $(+ self.$c.unsigned_abs())+
+            }
+
+            /// The Chebyshev norm: the number of king's moves it takes.
+            pub fn max_norm(&self) -> usize {
+                0 $(.max(self.$c.unsigned_abs()))+
+            }
+
+            /// Each component clamped to -1, 0 or 1: a single step in the same direction.
+            pub fn signum(&self) -> Self {
+                Self::new($(self.$c.signum()),+)
+            }
+        }
+
+        impl Add<$delta> for $pos {
+            type Output = Self;
+            fn add(self, rhs: $delta) -> Self::Output {
+                Self::new($(self.$c + rhs.$c),+)
+            }
+        }
+
+        impl Sub<$delta> for $pos {
+            type Output = Self;
+            fn sub(self, rhs: $delta) -> Self::Output {
+                Self::new($(self.$c - rhs.$c),+)
+            }
+        }
+
+        impl Sub<$pos> for $pos {
+            type Output = $delta;
+            fn sub(self, rhs: $pos) -> Self::Output {
+                $delta::new($(self.$c - rhs.$c),+)
+            }
+        }
+
+        impl AddAssign<$delta> for $pos {
+            fn add_assign(&mut self, rhs: $delta) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl SubAssign<$delta> for $pos {
+            fn sub_assign(&mut self, rhs: $delta) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl Add for $delta {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self::Output {
+                Self::new($(self.$c + rhs.$c),+)
+            }
+        }
+
+        impl Sub for $delta {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self::new($(self.$c - rhs.$c),+)
+            }
+        }
+
+        impl AddAssign for $delta {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl SubAssign for $delta {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl Neg for $delta {
+            type Output = Self;
+            fn neg(self) -> Self::Output {
+                Self::new($(-self.$c),+)
+            }
+        }
+
+        impl Mul<isize> for $delta {
+            type Output = Self;
+            fn mul(self, rhs: isize) -> Self::Output {
+                Self::new($(self.$c * rhs),+)
+            }
+        }
+    };
+}
+
+impl_vector_ops!(PosXY, DeltaXY, x, y);
+impl_vector_ops!(PosXYZ, DeltaXYZ, x, y, z);
+
+/// `value * numerator / denominator`, rounded to the nearest integer.
+fn round_fraction(value: isize, numerator: isize, denominator: isize) -> isize {
+    if denominator == 0 {
+        return 0;
+    }
+    (2 * value * numerator + denominator).div_euclid(2 * denominator)
+}
+
+impl DeltaXY {
+    /// A quarter turn counterclockwise.
+    pub fn rotate_left(&self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// A quarter turn clockwise.
+    pub fn rotate_right(&self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+}
+
+/// Quarter turns counterclockwise when looking from the positive end of the axis towards
+/// the origin, like the fingers of the right hand curl around the thumb.
+impl DeltaXYZ {
+    pub fn rotate_x(&self) -> Self {
+        Self::new(self.x, -self.z, self.y)
+    }
+
+    pub fn rotate_y(&self) -> Self {
+        Self::new(self.z, self.y, -self.x)
+    }
+
+    pub fn rotate_z(&self) -> Self {
+        Self::new(-self.y, self.x, self.z)
+    }
+}
+
+/// The smallest box around some points, its edges included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundsXY {
+    pub min: PosXY,
+    pub max: PosXY,
+}
+
+impl BoundsXY {
+    pub fn new(pos: PosXY) -> Self {
+        Self { min: pos, max: pos }
+    }
+
+    /// The bounds of `points`, or `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = PosXY>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bounds = Self::new(points.next()?);
+        points.for_each(|p| bounds.include(p));
+        Some(bounds)
+    }
+
+    /// Grow to include `pos`.
+    pub fn include(&mut self, pos: PosXY) {
+        self.min = PosXY::new(self.min.x.min(pos.x), self.min.y.min(pos.y));
+        self.max = PosXY::new(self.max.x.max(pos.x), self.max.y.max(pos.y));
+    }
+
+    pub fn contains(&self, pos: PosXY) -> bool {
+        (self.min.x..=self.max.x).contains(&pos.x) && (self.min.y..=self.max.y).contains(&pos.y)
+    }
+
+    pub fn width(&self) -> usize {
+        self.min.x.abs_diff(self.max.x) + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.min.y.abs_diff(self.max.y) + 1
+    }
+}
+
+/// See [`BoundsXY`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundsXYZ {
+    pub min: PosXYZ,
+    pub max: PosXYZ,
+}
+
+impl BoundsXYZ {
+    pub fn new(pos: PosXYZ) -> Self {
+        Self { min: pos, max: pos }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = PosXYZ>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bounds = Self::new(points.next()?);
+        points.for_each(|p| bounds.include(p));
+        Some(bounds)
+    }
+
+    pub fn include(&mut self, pos: PosXYZ) {
+        let (min, max) = (self.min, self.max);
+        self.min = PosXYZ::new(min.x.min(pos.x), min.y.min(pos.y), min.z.min(pos.z));
+        self.max = PosXYZ::new(max.x.max(pos.x), max.y.max(pos.y), max.z.max(pos.z));
+    }
+
+    pub fn contains(&self, pos: PosXYZ) -> bool {
+        (self.min.x..=self.max.x).contains(&pos.x)
+            && (self.min.y..=self.max.y).contains(&pos.y)
+            && (self.min.z..=self.max.z).contains(&pos.z)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BoundsXY, BoundsXYZ, DeltaXY, DeltaXYZ, PosXY, PosXYZ};
+
+    #[test]
+    fn arithmetic() {
+        let a = PosXY::new(1, 2);
+        let b = PosXY::new(4, -2);
+        assert_eq!(b - a, DeltaXY::new(3, -4));
+        assert_eq!(a + (b - a), b);
+        assert_eq!((b - a).manhattan_norm(), 7);
+        assert_eq!((b - a).max_norm(), 4);
+        assert_eq!((b - a).signum(), DeltaXY::new(1, -1));
+        assert_eq!(-(b - a) * 2, DeltaXY::new(-6, 8));
+
+        let d = DeltaXYZ::new(1, -5, 2);
+        assert_eq!(d.manhattan_norm(), 8);
+        assert_eq!(d.max_norm(), 5);
+        assert_eq!(PosXYZ::default() + d - d, PosXYZ::default());
+    }
+
+    #[test]
+    fn rotations() {
+        let east = DeltaXY::new(1, 0);
+        assert_eq!(east.rotate_left(), DeltaXY::new(0, 1));
+        assert_eq!(east.rotate_right(), DeltaXY::new(0, -1));
+        assert_eq!(
+            east.rotate_left().rotate_left().rotate_left(),
+            east.rotate_right()
+        );
+
+        let d = DeltaXYZ::new(1, 2, 3);
+        assert_eq!(DeltaXYZ::new(1, 0, 0).rotate_z(), DeltaXYZ::new(0, 1, 0));
+        assert_eq!(DeltaXYZ::new(0, 1, 0).rotate_x(), DeltaXYZ::new(0, 0, 1));
+        assert_eq!(DeltaXYZ::new(0, 0, 1).rotate_y(), DeltaXYZ::new(1, 0, 0));
+        assert_eq!(d.rotate_x().rotate_x().rotate_x().rotate_x(), d);
+        assert_eq!(d.rotate_y().manhattan_norm(), d.manhattan_norm());
+    }
+
+    #[test]
+    fn lines() {
+        let line = |a: PosXY, b| a.line_to(b).map(|p| (p.x, p.y)).collect::<Vec<_>>();
+        assert_eq!(
+            line(PosXY::new(0, 0), PosXY::new(3, 0)),
+            [(0, 0), (1, 0), (2, 0), (3, 0)]
+        );
+        assert_eq!(
+            line(PosXY::new(2, 2), PosXY::new(0, 0)),
+            [(2, 2), (1, 1), (0, 0)]
+        );
+        assert_eq!(line(PosXY::new(0, 0), PosXY::new(4, 2)).len(), 5);
+        assert_eq!(line(PosXY::new(5, 5), PosXY::new(5, 5)), [(5, 5)]);
+
+        let line3 = PosXYZ::new(0, 0, 0)
+            .line_to(PosXYZ::new(0, -2, 2))
+            .collect::<Vec<_>>();
+        assert_eq!(line3[1], PosXYZ::new(0, -1, 1));
+    }
+
+    #[test]
+    fn bounds() {
+        let points = [PosXY::new(1, -1), PosXY::new(-2, 3), PosXY::new(0, 0)];
+        let bounds = BoundsXY::from_points(points).expect("there are points");
+        assert_eq!(
+            (bounds.min, bounds.max),
+            (PosXY::new(-2, -1), PosXY::new(1, 3))
+        );
+        assert_eq!((bounds.width(), bounds.height()), (4, 5));
+        assert!(bounds.contains(PosXY::new(0, 3)));
+        assert!(!bounds.contains(PosXY::new(2, 0)));
+        assert_eq!(BoundsXY::from_points([]), None);
+
+        let bounds = BoundsXYZ::from_points([PosXYZ::new(0, 0, 0), PosXYZ::new(1, 2, -3)])
+            .expect("there are points");
+        assert!(bounds.contains(PosXYZ::new(1, 1, -1)));
+        assert!(!bounds.contains(PosXYZ::new(1, 1, 1)));
+    }
+}
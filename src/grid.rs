@@ -0,0 +1,422 @@
+//! Rectangular grids of cells, like day 8's forest, read from maps of characters.
+//!
+//! Positions are a row, counted down from the top, and a column, counted from the left.
+
+use std::{
+    fmt::Display,
+    iter::successors,
+    ops::{Index, IndexMut, Range},
+};
+
+use crate::parse::ParseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Pos {
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Pos {
+    pub fn new(row: usize, column: usize) -> Self {
+        Self { row, column }
+    }
+
+    /// The position `rows` down and `columns` right, if neither goes below 0.
+    pub fn offset(self, rows: isize, columns: isize) -> Option<Self> {
+        Some(Self::new(
+            self.row.checked_add_signed(rows)?,
+            self.column.checked_add_signed(columns)?,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    /// The change in row and column of a step.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+/// The offsets of the 8 neighbours, clockwise from the top left.
+const NEIGHBOURS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+/// Cells in rows of the same width.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    data: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// A grid with the value `cell(pos)` at every position.
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(Pos) -> T) -> Self {
+        let data = (0..height)
+            .flat_map(|row| (0..width).map(move |column| Pos::new(row, column)))
+            .map(&mut cell)
+            .collect();
+        Self {
+            data,
+            width,
+            height,
+        }
+    }
+
+    /// Read a map with one row per line, turning each character into a cell with `cell`.
+    ///
+    /// Empty lines at the end are dropped, but an empty line anywhere else is a missing row.
+    /// Errors say a character was expected to be `expected`, or that a row of the first
+    /// row's width of `cells` was expected.
+    pub fn parse(
+        s: &str,
+        expected: &str,
+        cells: &str,
+        cell: impl Fn(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut data = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        let lines = s.lines().collect::<Vec<_>>();
+        let rows = lines.len() - lines.iter().rev().take_while(|l| l.is_empty()).count();
+        for (index, &line) in lines[..rows].iter().enumerate() {
+            let error = |span: Range<usize>, expected: String| {
+                ParseError::at(line, span, expected).offset_lines(index)
+            };
+            if line.is_empty() {
+                let row = match width {
+                    Some(width) => format!("a row of {width} {cells}"),
+                    None => format!("a row of {cells}"),
+                };
+                Err(error(0..0, row))?;
+            }
+
+            for (i, c) in line.char_indices() {
+                let value = cell(c).ok_or_else(|| error(i..i + c.len_utf8(), expected.into()))?;
+                data.push(value);
+            }
+
+            let line_length = line.chars().count();
+            let width = *width.get_or_insert(line_length);
+            if line_length != width {
+                let end = line
+                    .char_indices()
+                    .nth(width)
+                    .map_or(line.len(), |(i, _)| i);
+                Err(error(end..line.len(), format!("a row of {width} {cells}")))?;
+            }
+
+            height += 1;
+        }
+
+        let width = width
+            .ok_or_else(|| ParseError::new("", 1, format!("a row of {cells}"), "end of input"))?;
+        Ok(Self {
+            data,
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.row < self.height && pos.column < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.data[pos.row * self.width + pos.column])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.data[pos.row * self.width + pos.column])
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |column| Pos::new(row, column)))
+    }
+
+    /// Every cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.data.iter()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` can't take 0, and a grid without columns has no cells anyway.
+        self.data.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        assert!(column < self.width, "column {column} of {}", self.width);
+        self.data.iter().skip(column).step_by(self.width)
+    }
+
+    /// The position a step away, if it is on the grid.
+    pub fn step(&self, pos: Pos, direction: Direction) -> Option<Pos> {
+        let (rows, columns) = direction.offset();
+        pos.offset(rows, columns).filter(|&p| self.contains(p))
+    }
+
+    /// The positions from `pos` towards the edge in `direction`, without `pos` itself.
+    pub fn ray(&self, pos: Pos, direction: Direction) -> impl Iterator<Item = Pos> + '_ {
+        successors(self.step(pos, direction), move |&p| self.step(p, direction))
+    }
+
+    /// The positions along one side, like the top row for [`Direction::Up`].
+    pub fn edge(&self, side: Direction) -> impl Iterator<Item = Pos> {
+        let (width, height) = (self.width, self.height);
+        let (count, pos): (usize, Box<dyn Fn(usize) -> Pos>) = match side {
+            Direction::Up => (width, Box::new(|i| Pos::new(0, i))),
+            Direction::Down => (width, Box::new(move |i| Pos::new(height - 1, i))),
+            Direction::Left => (height, Box::new(|i| Pos::new(i, 0))),
+            Direction::Right => (height, Box::new(move |i| Pos::new(i, width - 1))),
+        };
+        let count = if width == 0 || height == 0 { 0 } else { count };
+        (0..count).map(pos)
+    }
+
+    /// The neighbours up, down, left and right that are on the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |d| self.step(pos, d))
+    }
+
+    /// The neighbours that are on the grid, diagonals included.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBOURS8
+            .into_iter()
+            .filter_map(move |(rows, columns)| pos.offset(rows, columns))
+            .filter(|&p| self.contains(p))
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            data: self.data.iter().map(&mut f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Draw the grid with a character per cell, each row ending in `\n`.
+    pub fn render(&self, cell: impl Fn(&T) -> char) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for row in 0..self.height {
+            out.extend(self.row(row).iter().map(&cell));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self {
+            data: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Swap rows and columns.
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |p| {
+            self[Pos::new(p.column, p.row)].clone()
+        })
+    }
+
+    /// Turn a quarter clockwise, so the left column becomes the top row.
+    pub fn rotate_clockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |p| {
+            self[Pos::new(self.height - 1 - p.column, p.row)].clone()
+        })
+    }
+
+    /// Turn a quarter counterclockwise, so the top row becomes the left column.
+    pub fn rotate_counterclockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |p| {
+            self[Pos::new(p.column, self.width - 1 - p.row)].clone()
+        })
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        let (width, height) = (self.width, self.height);
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the {width}x{height} grid"))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the {width}x{height} grid"))
+    }
+}
+
+/// The cells as a map, which [`Grid::parse`] reads back if every cell is one character.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                cell.fmt(f)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Direction, Grid, Pos};
+    use crate::parse::ParseError;
+    use anyhow::Result;
+
+    fn digits(s: &str) -> Result<Grid<u32>, ParseError> {
+        Grid::parse(s, "a digit", "digits", |c| c.to_digit(10))
+    }
+
+    #[test]
+    fn parse_and_display() -> Result<()> {
+        let grid = digits("123\n456\n\n\n")?;
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos::new(1, 2)], 6);
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(
+            grid.render(|&d| if d % 2 == 0 { '#' } else { '.' }),
+            ".#.\n#.#\n"
+        );
+
+        let error = digits("12\n345\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.expected, "a row of 2 digits");
+        let error = digits("12\n3x\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.expected.as_str()),
+            (2, 2, "a digit")
+        );
+        let error = digits("12\n\n34\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.expected.as_str()),
+            (2, 1, "a row of 2 digits")
+        );
+        assert_eq!(error.found, "end of line");
+        assert_eq!(digits("\n12\n").unwrap_err().expected, "a row of digits");
+        assert!(digits("").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn bounds() -> Result<()> {
+        let grid = digits("12\n34\n")?;
+        assert_eq!(grid.get(Pos::new(1, 1)), Some(&4));
+        assert_eq!(grid.get(Pos::new(0, 2)), None);
+        assert_eq!(grid.get(Pos::new(2, 0)), None);
+        assert_eq!(grid.step(Pos::new(0, 0), Direction::Up), None);
+        assert_eq!(
+            grid.step(Pos::new(0, 0), Direction::Right),
+            Some(Pos::new(0, 1))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn neighbours() -> Result<()> {
+        let grid = digits("123\n456\n789\n")?;
+        let values = |positions: Vec<Pos>| positions.iter().map(|&p| grid[p]).collect::<Vec<_>>();
+        assert_eq!(values(grid.neighbours4(Pos::new(0, 0)).collect()), [4, 2]);
+        assert_eq!(
+            values(grid.neighbours4(Pos::new(1, 1)).collect()),
+            [2, 8, 4, 6]
+        );
+        assert_eq!(
+            values(grid.neighbours8(Pos::new(0, 0)).collect()),
+            [2, 5, 4]
+        );
+        assert_eq!(
+            values(grid.neighbours8(Pos::new(1, 1)).collect()),
+            [1, 2, 3, 6, 9, 8, 7, 4]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rows_columns_and_rays() -> Result<()> {
+        let grid = digits("123\n456\n")?;
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), [3, 6]);
+        assert_eq!(grid.rows().count(), 2);
+        let ray = |pos, d| grid.ray(pos, d).map(|p| grid[p]).collect::<Vec<_>>();
+        assert_eq!(ray(Pos::new(0, 0), Direction::Right), [2, 3]);
+        assert_eq!(ray(Pos::new(1, 2), Direction::Up), [3]);
+        assert!(ray(Pos::new(1, 2), Direction::Down).is_empty());
+        let edge = |side| grid.edge(side).map(|p| grid[p]).collect::<Vec<_>>();
+        assert_eq!(edge(Direction::Down), [4, 5, 6]);
+        assert_eq!(edge(Direction::Right), [3, 6]);
+        Ok(())
+    }
+
+    #[test]
+    fn transpose_and_rotate() -> Result<()> {
+        let grid = digits("123\n456\n")?;
+        assert_eq!(grid.transpose().to_string(), "14\n25\n36\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "41\n52\n63\n");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "36\n25\n14\n");
+        assert_eq!(grid.rotate_clockwise().rotate_counterclockwise(), grid);
+        assert_eq!(grid.map(|d| d * 2).transpose()[Pos::new(2, 1)], 12);
+        assert_eq!(Grid::filled(2, 1, 0).to_string(), "00\n");
+        Ok(())
+    }
+}
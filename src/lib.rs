@@ -1,5 +1,6 @@
-use aoc_runner_derive::aoc_lib;
-
+pub mod allocations;
+pub mod checked;
+pub mod client;
 pub mod day1;
 pub mod day10;
 pub mod day11;
@@ -11,5 +12,29 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+pub mod differential;
+pub mod examples;
+pub mod generate;
+pub mod geom;
+pub mod grid;
+pub mod manifest;
+pub mod normalize;
+pub mod parse;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod stream;
+pub mod submit;
+
+/// The year of the days in `src/dayN.rs`, and of every command run without `--year`.
+/// Other years live in `src/yearYYYY/`.
+pub const DEFAULT_YEAR: u32 = 2022;
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: allocations::CountingAllocator = allocations::CountingAllocator;
 
-aoc_lib! { year = 2022 }
+pub use registry::registry;
+pub use solution::{Answer, Part, Solution, Variant};
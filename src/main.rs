@@ -1,5 +1,940 @@
-use aoc_runner_derive::aoc_main;
+use std::{
+    collections::HashMap,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{Duration, Instant},
+};
 
-extern crate aoc_2022;
+use anyhow::{anyhow, Result};
+use aoc_2022::{
+    allocations::{format_bytes, measure, AllocStats},
+    client::{Client, Config},
+    differential,
+    generate::{self, ParamValue},
+    manifest::{default_manifest_path, Check, Manifest},
+    parse::ParseError,
+    registry,
+    report::{self, Format, Writer},
+    runner::{
+        default_input_path, input_dir, open_input, read_input, run_day, run_days_parallel, DayRun,
+        DaySelection,
+    },
+    scaffold,
+    submit::{Outcome, Submission, SubmissionLog, Submitter},
+    Answer, Part, Solution, Variant, DEFAULT_YEAR,
+};
+use clap::{Args, Parser, Subcommand};
+use tracing_subscriber::EnvFilter;
 
-aoc_main! { lib = aoc_2022 }
+#[derive(Parser)]
+#[command(about = "Advent of code, 2022 and on")]
+struct Cli {
+    /// Log level or filter for the trace of generators and solvers, like `debug` or
+    /// `aoc_2022::day11=trace` (default: $RUST_LOG, or `warn`)
+    #[arg(long, global = true, value_name = "FILTER")]
+    log: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the implemented days and their solvers
+    List,
+    /// Solve and print the answers with timings
+    Run {
+        #[command(flatten)]
+        selection: Selection,
+        /// Print `text`, or one record per solver as `json` lines or `csv`
+        #[arg(short, long, default_value = "text")]
+        format: Format,
+        /// Read the input bit by bit instead of all at once, for the parts that can be
+        /// (see `list`)
+        #[arg(long, conflicts_with_all = ["format", "variant"])]
+        stream: bool,
+    },
+    /// Run the solvers repeatedly and print timing statistics
+    Bench {
+        #[command(flatten)]
+        selection: Selection,
+        /// Number of timed runs per generator and part
+        #[arg(short = 'n', long, default_value_t = 10)]
+        iterations: u32,
+    },
+    /// Start a new day from src/dayX.rs, in src/yearYYYY/ for years other than 2022
+    NewDay {
+        day: u32,
+        #[arg(short, long, default_value_t = DEFAULT_YEAR)]
+        year: u32,
+    },
+    /// Generate a random valid input of a 2022 day, for stress and scale testing
+    Gen {
+        day: u32,
+        /// The same seed and parameters always give the same input
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
+        /// Tune the input, like `-p width=1000`; see `--list-params` for each day's parameters
+        #[arg(short = 'p', long = "param")]
+        params: Vec<ParamValue>,
+        /// Write the input here instead of to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Print the day's parameters and their defaults instead
+        #[arg(long)]
+        list_params: bool,
+    },
+    /// Check that a 2022 day's variants and cross-checks agree on generated inputs, and
+    /// shrink the first input they disagree on
+    Diff {
+        day: u32,
+        /// Number of inputs to try
+        #[arg(short = 'n', long, default_value_t = 100)]
+        seeds: u64,
+        /// The seed of the first input
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
+        /// Tune the inputs, like `gen -p`
+        #[arg(short = 'p', long = "param")]
+        params: Vec<ParamValue>,
+    },
+    /// Download missing puzzle inputs into input/YEAR/
+    Fetch {
+        /// Days to fetch, like `5`, `1-5`, `1,3,7-9` or `all` (the implemented days)
+        #[arg(default_value = "all")]
+        days: DaySelection,
+        #[arg(short, long, default_value_t = DEFAULT_YEAR)]
+        year: u32,
+    },
+    /// Submit an answer, computed from the real input unless given
+    Submit {
+        day: u32,
+        part: Part,
+        /// The answer to submit
+        #[arg(short, long)]
+        answer: Option<String>,
+        #[arg(short, long, default_value_t = DEFAULT_YEAR)]
+        year: u32,
+        /// Known answers, updated when an answer is accepted (default:
+        /// input/YEAR/answers.toml)
+        #[arg(short, long)]
+        manifest: Option<PathBuf>,
+    },
+    /// Check that every solver succeeds, that all variants of a part agree and,
+    /// for the real inputs, that the answers match the answer manifest
+    Verify {
+        #[command(flatten)]
+        selection: Selection,
+        /// Known answers for the real inputs (default: input/YEAR/answers.toml)
+        #[arg(short, long)]
+        manifest: Option<PathBuf>,
+    },
+    /// Solve every day and part in parallel and print a table of answers, timings and
+    /// whether they match the answer manifest
+    RunAll {
+        #[command(flatten)]
+        selection: Selection,
+        /// Known answers for the real inputs (default: input/YEAR/answers.toml)
+        #[arg(short, long)]
+        manifest: Option<PathBuf>,
+        /// Number of threads (default: one per CPU)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+        /// Print a `text` table, or one record per solver as `json` lines or `csv`
+        #[arg(short, long, default_value = "text")]
+        format: Format,
+    },
+}
+
+#[derive(Args)]
+struct Selection {
+    /// Days to run, like `5`, `1-5`, `1,3,7-9` or `all` (the default)
+    days: Option<DaySelection>,
+    /// The same as the days above, for those who prefer `--day 5`
+    #[arg(short, long, value_name = "DAYS", conflicts_with = "days")]
+    day: Option<DaySelection>,
+    #[arg(short, long, default_value_t = DEFAULT_YEAR)]
+    year: u32,
+    /// Only run this part
+    #[arg(short, long)]
+    part: Option<Part>,
+    /// Only run the variant with this name
+    #[arg(short, long)]
+    variant: Option<String>,
+    /// Input file, or `-` for stdin (default: input/YEAR/dayN.txt)
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+}
+
+impl Selection {
+    fn days(&self) -> DaySelection {
+        self.day
+            .clone()
+            .or_else(|| self.days.clone())
+            .unwrap_or(DaySelection::All)
+    }
+
+    fn solutions(&self) -> Result<Vec<&'static dyn Solution>> {
+        let year = registry().year(self.year);
+        let days = self.days();
+        if let DaySelection::Days(days) = &days {
+            if let Some(day) = days.iter().find(|&&d| year.get(d).is_none()) {
+                Err(anyhow!("day {day} of {} is not implemented", self.year))?;
+            }
+        }
+        let solutions = year
+            .iter()
+            .filter(|s| days.contains(s.day()))
+            .collect::<Vec<_>>();
+        if self.input.is_some() && solutions.len() != 1 {
+            Err(anyhow!("--input needs exactly one day to be selected"))?;
+        }
+        Ok(solutions)
+    }
+
+    fn input_path(&self, day: u32) -> PathBuf {
+        match &self.input {
+            Some(path) => path.clone(),
+            None => default_input_path(self.year, day),
+        }
+    }
+
+    /// The manifest for the selected year, unless another one is given.
+    fn manifest_path(&self, manifest: Option<&Path>) -> PathBuf {
+        manifest.map_or_else(|| default_manifest_path(self.year), Path::to_path_buf)
+    }
+
+    /// How the input of `day` is named in error messages.
+    fn input_name(&self, day: u32) -> String {
+        match self.input_path(day) {
+            path if path == Path::new("-") => "<stdin>".to_string(),
+            path => path.display().to_string(),
+        }
+    }
+
+    fn accepts(&self, variant: &Variant) -> bool {
+        let part_ok = self.part.is_none_or(|part| variant.part == part);
+        let variant_ok = match (&self.variant, variant.name) {
+            (None, _) => true,
+            (Some(wanted), Some(name)) => wanted.eq_ignore_ascii_case(name),
+            (Some(_), None) => false,
+        };
+        part_ok && variant_ok
+    }
+
+    /// Read the input of every selected day and run the accepted solvers, which also get
+    /// the name of the input.
+    ///
+    /// Days whose input can't be read are reported and counted as failures.
+    fn run_each(&self, mut f: impl FnMut(&dyn Solution, &str, &str) -> bool) -> Result<bool> {
+        let mut all_ok = true;
+        for solution in self.solutions()? {
+            let day = solution.day();
+            match read_input(&self.input_path(day)) {
+                Ok(input) => all_ok &= f(solution, &self.input_name(day), &input),
+                Err(e) => {
+                    eprintln!("Day {day}: FAILED while reading input:\n{e:?}\n");
+                    all_ok = false;
+                }
+            }
+        }
+        Ok(all_ok)
+    }
+}
+
+fn label(day: u32, variant: &Variant) -> String {
+    match variant.name {
+        Some(name) => format!("Day {day} - Part {} - {name}", variant.part),
+        None => format!("Day {day} - Part {}", variant.part),
+    }
+}
+
+fn format_answer(answer: &Answer) -> String {
+    let text = answer.to_string();
+    if text.contains('\n') {
+        format!("\n{text}")
+    } else {
+        format!(" {text}")
+    }
+}
+
+fn list() {
+    for solution in registry().iter() {
+        let variants = solution
+            .variants()
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let streamed = solution
+            .stream_parts()
+            .iter()
+            .map(|p| format!("part{p}"))
+            .collect::<Vec<_>>();
+        let streamed = match streamed.is_empty() {
+            true => String::new(),
+            false => format!("; streamed: {}", streamed.join(", ")),
+        };
+        match solution.year() {
+            DEFAULT_YEAR => println!("Day {}: {variants}{streamed}", solution.day()),
+            year => println!("{year} Day {}: {variants}{streamed}", solution.day()),
+        }
+    }
+}
+
+/// The allocations after a time, when they were counted.
+fn format_allocations(allocations: Option<AllocStats>) -> String {
+    allocations.map_or(String::new(), |stats| format!(" ({stats})"))
+}
+
+/// Report a failed generator, showing parse errors within the input they came from.
+fn print_generator_error(day: u32, e: &anyhow::Error, input_name: &str, input: &str) {
+    match e.downcast_ref::<ParseError>() {
+        Some(error) => eprintln!(
+            "Day {day}: FAILED while generating:\n{}",
+            error.render(input_name, input)
+        ),
+        None => eprintln!("Day {day}: FAILED while generating:\n{e:?}\n"),
+    }
+}
+
+fn print_run(run: &DayRun, input_name: &str, input: &str) {
+    let day = run.day;
+    let parts = match &run.parts {
+        Ok(parts) => parts,
+        Err(e) => {
+            print_generator_error(day, e, input_name, input);
+            return;
+        }
+    };
+    for part in parts {
+        let label = label(day, &part.variant);
+        match &part.answer {
+            Ok(answer) => println!(
+                "{label}:{}\n\tgenerator: {:?}{},\n\trunner: {:?}{}\n",
+                format_answer(answer),
+                run.parse_time,
+                format_allocations(run.parse_allocations),
+                part.elapsed,
+                format_allocations(part.allocations),
+            ),
+            Err(e) => eprintln!("{label}: FAILED while running:\n{e:?}\n"),
+        }
+    }
+}
+
+fn run(selection: &Selection, format: Format) -> Result<bool> {
+    if format == Format::Text {
+        return selection.run_each(|solution, input_name, input| {
+            let run = run_day(solution, input, |v| selection.accepts(v));
+            print_run(&run, input_name, input);
+            run.is_ok()
+        });
+    }
+
+    let mut records = vec![];
+    let all_ok = selection.run_each(|solution, _, input| {
+        let run = run_day(solution, input, |v| selection.accepts(v));
+        records.extend(report::records(
+            solution,
+            &run,
+            input,
+            |v| selection.accepts(v),
+            None,
+        ));
+        run.is_ok()
+    })?;
+    let mut writer = Writer::new(format, std::io::stdout().lock())?;
+    for record in &records {
+        writer.write(record)?;
+    }
+    writer.flush()?;
+    Ok(all_ok)
+}
+
+/// Solve the parts that can be streamed, reading the input as they go. Each part reads the
+/// input again.
+fn run_streamed(selection: &Selection) -> Result<bool> {
+    let mut all_ok = true;
+    for solution in selection.solutions()? {
+        let day = solution.day();
+        let parts = solution
+            .stream_parts()
+            .into_iter()
+            .filter(|&part| selection.part.is_none_or(|p| p == part))
+            .collect::<Vec<_>>();
+        if parts.is_empty() {
+            eprintln!("Day {day}: can't be streamed, skipped\n");
+            continue;
+        }
+        let path = selection.input_path(day);
+        if path == Path::new("-") && parts.len() > 1 {
+            Err(anyhow!("stdin can only be streamed once, pick a --part"))?;
+        }
+
+        for part in parts {
+            let start_time = Instant::now();
+            let answer =
+                open_input(&path).and_then(|mut input| solution.solve_stream(&mut input, part));
+            let elapsed = start_time.elapsed();
+            match answer {
+                Ok(answer) => println!(
+                    "Day {day} - Part {part}:{}\n\tstreamed: {elapsed:?}\n",
+                    format_answer(&answer)
+                ),
+                Err(e) => {
+                    eprintln!("Day {day} - Part {part}: FAILED while streaming:\n{e:?}\n");
+                    all_ok = false;
+                }
+            }
+        }
+    }
+    Ok(all_ok)
+}
+
+#[derive(Default)]
+struct Timings {
+    times: Vec<Duration>,
+    /// Of the last run, as every run allocates the same.
+    allocations: Option<AllocStats>,
+}
+
+impl Timings {
+    fn time<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        let ((result, elapsed), allocations) = measure(|| {
+            let start_time = Instant::now();
+            let result = f();
+            (result, start_time.elapsed())
+        });
+        self.times.push(elapsed);
+        self.allocations = allocations;
+        result
+    }
+
+    fn summary(&self) -> String {
+        let min = self.times.iter().min().copied().unwrap_or_default();
+        let max = self.times.iter().max().copied().unwrap_or_default();
+        let mean = self.times.iter().sum::<Duration>() / (self.times.len().max(1) as u32);
+        format!(
+            "min {min:?}, mean {mean:?}, max {max:?}{}",
+            format_allocations(self.allocations)
+        )
+    }
+}
+
+fn bench(selection: &Selection, iterations: u32) -> Result<bool> {
+    selection.run_each(|solution, input_name, input| {
+        let day = solution.day();
+        let mut parse_timings = Timings::default();
+        let mut data = None;
+        for _ in 0..iterations.max(1) {
+            match parse_timings.time(|| solution.parse(input)) {
+                Ok(parsed) => data = Some(parsed),
+                Err(e) => {
+                    print_generator_error(day, &e, input_name, input);
+                    return false;
+                }
+            }
+        }
+        let Some(data) = data else { return false };
+        println!("Day {day} - generator: {}", parse_timings.summary());
+
+        let mut all_ok = true;
+        for variant in solution.variants() {
+            if !selection.accepts(&variant) {
+                continue;
+            }
+            let label = label(day, &variant);
+            let mut timings = Timings::default();
+            for _ in 0..iterations.max(1) {
+                if let Err(e) = timings.time(|| solution.solve(&data, variant)) {
+                    eprintln!("{label}: FAILED while running:\n{e:?}\n");
+                    all_ok = false;
+                    break;
+                }
+            }
+            println!("{label}: {}", timings.summary());
+        }
+        println!();
+        all_ok
+    })
+}
+
+fn verify(selection: &Selection, manifest: Option<&Path>) -> Result<bool> {
+    // The manifest only knows the answers for the real inputs.
+    let manifest = match selection.input {
+        Some(_) => Manifest::default(),
+        None => Manifest::load(selection.manifest_path(manifest))?,
+    };
+
+    selection.run_each(|solution, input_name, input| {
+        let run = run_day(solution, input, |v| selection.accepts(v));
+        let day = run.day;
+        let parts = match &run.parts {
+            Ok(parts) => parts,
+            Err(e) => {
+                print_generator_error(day, e, input_name, input);
+                return false;
+            }
+        };
+
+        let mut all_ok = true;
+        let mut first_answers = HashMap::<Part, (&Variant, &Answer)>::new();
+        for part in parts {
+            let this = label(day, &part.variant);
+            let answer = match &part.answer {
+                Ok(answer) => answer,
+                Err(e) => {
+                    eprintln!("{this}: FAILED while running:\n{e:?}\n");
+                    all_ok = false;
+                    continue;
+                }
+            };
+            let (first, expected) = *first_answers
+                .entry(part.variant.part)
+                .or_insert((&part.variant, answer));
+            if answer != expected {
+                eprintln!(
+                    "{this}: FAILED, disagrees with {}:\n{}\n  vs\n{}\n",
+                    label(day, first),
+                    answer,
+                    expected
+                );
+                all_ok = false;
+                continue;
+            }
+            match manifest.check(day, part.variant.part, answer) {
+                Check::Correct => println!("{this}: ok"),
+                Check::Unknown => println!("{this}: ok (no known answer)"),
+                Check::Wrong { expected } => {
+                    eprintln!("{this}: FAILED, expected:\n{expected}\n  but got\n{answer}\n");
+                    all_ok = false;
+                }
+            }
+        }
+        all_ok
+    })
+}
+
+/// One line of the `run-all` table.
+struct SummaryRow {
+    day: u32,
+    variant: Variant,
+    answer: String,
+    parse_time: Duration,
+    solve_time: Option<Duration>,
+    /// Peak memory of the solver, when allocations were counted.
+    peak: Option<u64>,
+    status: &'static str,
+}
+
+/// Answers that don't fit on one line, like bitmaps, are shown by their size only.
+fn summary_answer(answer: &Answer) -> String {
+    let text = answer.to_string();
+    match text.lines().count() {
+        0 | 1 => text,
+        lines => format!("({lines} lines)"),
+    }
+}
+
+fn print_summary(rows: &[SummaryRow]) {
+    let peaks = rows.iter().any(|row| row.peak.is_some());
+    let mut header = vec!["Day", "Part", "Variant", "Answer", "Parse", "Solve"];
+    if peaks {
+        header.push("Peak");
+    }
+    header.push("Result");
+    let cells = rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![
+                row.day.to_string(),
+                row.variant.part.to_string(),
+                row.variant.name.unwrap_or("-").to_string(),
+                row.answer.clone(),
+                format!("{:.1?}", row.parse_time),
+                row.solve_time
+                    .map_or("-".to_string(), |t| format!("{t:.1?}")),
+            ];
+            if peaks {
+                cells.push(row.peak.map_or("-".to_string(), format_bytes));
+            }
+            cells.push(row.status.to_string());
+            cells
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = header.iter().map(|h| h.len()).collect::<Vec<_>>();
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &[String]| {
+        let padded = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>();
+        println!("{}", padded.join("  ").trim_end());
+    };
+
+    line(&header.iter().map(|h| h.to_string()).collect::<Vec<_>>());
+    line(&widths.iter().map(|&w| "-".repeat(w)).collect::<Vec<_>>());
+    for row in &cells {
+        line(row);
+    }
+}
+
+fn run_all(
+    selection: &Selection,
+    manifest: Option<&Path>,
+    jobs: Option<usize>,
+    format: Format,
+) -> Result<bool> {
+    if let Some(jobs) = jobs {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()?;
+    }
+    // The manifest only knows the answers for the real inputs.
+    let manifest = match selection.input {
+        Some(_) => Manifest::default(),
+        None => Manifest::load(selection.manifest_path(manifest))?,
+    };
+
+    let mut all_ok = true;
+    let mut days = vec![];
+    let mut inputs = vec![];
+    for solution in selection.solutions()? {
+        let day = solution.day();
+        match read_input(&selection.input_path(day)) {
+            Ok(input) => {
+                days.push(solution);
+                inputs.push(input);
+            }
+            Err(e) => {
+                eprintln!("Day {day}: FAILED while reading input:\n{e:?}\n");
+                all_ok = false;
+            }
+        }
+    }
+
+    let start_time = Instant::now();
+    let jobs = days
+        .iter()
+        .zip(&inputs)
+        .map(|(&solution, input)| (solution, input.as_str()))
+        .collect::<Vec<_>>();
+    let runs = run_days_parallel(&jobs, |v| selection.accepts(v));
+    let wall_time = start_time.elapsed();
+
+    if format != Format::Text {
+        let mut writer = Writer::new(format, std::io::stdout().lock())?;
+        for ((solution, input), run) in jobs.iter().zip(&runs) {
+            let records = report::records(
+                *solution,
+                run,
+                input,
+                |v| selection.accepts(v),
+                Some(&manifest),
+            );
+            for record in records {
+                all_ok &= record.error.is_none() && record.check != Some("wrong");
+                writer.write(&record)?;
+            }
+        }
+        writer.flush()?;
+        return Ok(all_ok);
+    }
+
+    let mut rows = vec![];
+    for ((solution, input), run) in jobs.iter().zip(&runs) {
+        let day = run.day;
+        let parts = match &run.parts {
+            Ok(parts) => parts,
+            Err(e) => {
+                print_generator_error(day, e, &selection.input_name(day), input);
+                for variant in solution.variants() {
+                    if selection.accepts(&variant) {
+                        rows.push(SummaryRow {
+                            day,
+                            variant,
+                            answer: "-".to_string(),
+                            parse_time: run.parse_time,
+                            solve_time: None,
+                            peak: None,
+                            status: "FAILED",
+                        });
+                    }
+                }
+                all_ok = false;
+                continue;
+            }
+        };
+        for part in parts {
+            let (answer, status) = match &part.answer {
+                Ok(answer) => {
+                    let status = match manifest.check(day, part.variant.part, answer) {
+                        Check::Correct => "ok",
+                        Check::Unknown => "unknown",
+                        Check::Wrong { expected } => {
+                            eprintln!(
+                                "{}: FAILED, expected:\n{expected}\n  but got\n{answer}\n",
+                                label(day, &part.variant)
+                            );
+                            "WRONG"
+                        }
+                    };
+                    (summary_answer(answer), status)
+                }
+                Err(e) => {
+                    eprintln!(
+                        "{}: FAILED while running:\n{e:?}\n",
+                        label(day, &part.variant)
+                    );
+                    ("-".to_string(), "FAILED")
+                }
+            };
+            all_ok &= matches!(status, "ok" | "unknown");
+            rows.push(SummaryRow {
+                day,
+                variant: part.variant,
+                answer,
+                parse_time: run.parse_time,
+                solve_time: Some(part.elapsed),
+                peak: part.allocations.map(|a| a.peak),
+                status,
+            });
+        }
+    }
+
+    rows.sort_by_key(|row| (row.day, row.variant.part, row.variant.name));
+    print_summary(&rows);
+    let threads = rayon::current_num_threads();
+    println!(
+        "\n{} solvers in {wall_time:.1?} on {threads} thread{}",
+        rows.len(),
+        if threads == 1 { "" } else { "s" }
+    );
+    Ok(all_ok)
+}
+
+fn new_day(year: u32, day: u32) -> Result<bool> {
+    for path in scaffold::new_day(Path::new("."), year, day)? {
+        println!("wrote {}", path.display());
+    }
+    Ok(true)
+}
+
+fn gen(
+    day: u32,
+    seed: u64,
+    params: &[ParamValue],
+    output: Option<&Path>,
+    list_params: bool,
+) -> Result<bool> {
+    let generator =
+        generate::generator(day).ok_or_else(|| anyhow!("day {day} has no input generator"))?;
+    if list_params {
+        for param in generator.params {
+            println!("{} (default {}): {}", param.name, param.default, param.help);
+        }
+        return Ok(true);
+    }
+
+    let input = generator.generate(seed, params)?;
+    match output {
+        Some(path) => std::fs::write(path, input)
+            .map_err(|e| anyhow!("could not write {}: {e}", path.display()))?,
+        None => print!("{input}"),
+    }
+    Ok(true)
+}
+
+fn diff(day: u32, seeds: u64, seed: u64, params: &[ParamValue]) -> Result<bool> {
+    let generator =
+        generate::generator(day).ok_or_else(|| anyhow!("day {day} has no input generator"))?;
+    let solution = registry()
+        .get(day)
+        .ok_or_else(|| anyhow!("day {day} is not implemented"))?;
+    let checks = differential::checks(solution);
+    if checks.is_empty() {
+        println!("Day {day} has no variants or cross-checks to compare");
+        return Ok(true);
+    }
+
+    let search = differential::find(solution, generator, seed..seed + seeds, params)?;
+    match search.disagreement {
+        Some(disagreement) => {
+            print!("{disagreement}");
+            Ok(false)
+        }
+        None => {
+            let checks = search
+                .coverage
+                .iter()
+                .map(|(check, c)| {
+                    format!("{check}: {} compared, {} skipped", c.compared, c.skipped)
+                })
+                .collect::<Vec<_>>();
+            println!(
+                "Day {day}: no disagreement on {seeds} inputs ({})",
+                checks.join("; ")
+            );
+            Ok(true)
+        }
+    }
+}
+
+fn fetch(year: u32, days: &DaySelection) -> Result<bool> {
+    let days = match days {
+        DaySelection::All => registry().year(year).days().collect(),
+        DaySelection::Days(days) => days.clone(),
+    };
+
+    let client = Client::new(Config::load()?);
+    let mut all_ok = true;
+    let dir = input_dir(year);
+    for day in days {
+        match client.ensure_input(year, day, &dir) {
+            Ok((path, true)) => println!("Day {day}: downloaded {}", path.display()),
+            Ok((path, false)) => println!("Day {day}: {} already exists", path.display()),
+            Err(e) => {
+                eprintln!("Day {day}: FAILED while fetching:\n{e:?}\n");
+                all_ok = false;
+            }
+        }
+    }
+    Ok(all_ok)
+}
+
+fn submit(
+    year: u32,
+    day: u32,
+    part: Part,
+    answer: Option<&str>,
+    manifest: Option<&Path>,
+) -> Result<bool> {
+    let manifest = manifest.map_or_else(|| default_manifest_path(year), Path::to_path_buf);
+    let client = Client::new(Config::load()?);
+    let log = SubmissionLog::open(client.config().state_dir.join("submissions.log"))?;
+    let mut submitter = Submitter {
+        client: &client,
+        year,
+        log,
+        manifest_path: &manifest,
+    };
+
+    let submission = match answer {
+        Some(answer) => submitter.submit_text(day, part, answer)?,
+        None => {
+            let solution = registry()
+                .get_in(year, day)
+                .ok_or_else(|| anyhow!("day {day} of {year} is not implemented"))?;
+            let answer = solution.run(&read_input(&default_input_path(year, day))?, part)?;
+            println!("Day {day} - Part {part}: {answer}");
+            submitter.submit(day, part, &answer)?
+        }
+    };
+
+    match submission {
+        Submission::Submitted(outcome) => {
+            println!("Submitted: {outcome}");
+            Ok(outcome == Outcome::Correct)
+        }
+        Submission::Known { correct: true, .. } => {
+            println!(
+                "Already solved, and the answer matches {}",
+                manifest.display()
+            );
+            Ok(true)
+        }
+        Submission::Known { expected, .. } => {
+            eprintln!("Already solved with a different answer:\n{expected}");
+            Ok(false)
+        }
+    }
+}
+
+/// Log to stderr, so traces don't mix with the answers.
+fn init_logging(filter: Option<&str>) -> Result<()> {
+    let filter = match filter {
+        Some(filter) => EnvFilter::try_new(filter)?,
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .init();
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Err(e) = init_logging(cli.log.as_deref()) {
+        eprintln!("error: bad --log filter: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    let result = match &cli.command {
+        Command::List => {
+            list();
+            Ok(true)
+        }
+        Command::Run {
+            selection,
+            stream: true,
+            ..
+        } => run_streamed(selection),
+        Command::Run {
+            selection, format, ..
+        } => run(selection, *format),
+        Command::Bench {
+            selection,
+            iterations,
+        } => bench(selection, *iterations),
+        Command::NewDay { day, year } => new_day(*year, *day),
+        Command::Gen {
+            day,
+            seed,
+            params,
+            output,
+            list_params,
+        } => gen(*day, *seed, params, output.as_deref(), *list_params),
+        Command::Diff {
+            day,
+            seeds,
+            seed,
+            params,
+        } => diff(*day, *seeds, *seed, params),
+        Command::Fetch { days, year } => fetch(*year, days),
+        Command::Submit {
+            day,
+            part,
+            answer,
+            year,
+            manifest,
+        } => submit(*year, *day, *part, answer.as_deref(), manifest.as_deref()),
+        Command::Verify {
+            selection,
+            manifest,
+        } => verify(selection, manifest.as_deref()),
+        Command::RunAll {
+            selection,
+            manifest,
+            jobs,
+            format,
+        } => run_all(selection, manifest.as_deref(), *jobs, *format),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {e:?}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::{
+    runner::input_dir,
+    solution::{Answer, Part},
+};
+
+/// Where the known answers for the real inputs of `year` live, relative to the repository
+/// root.
+pub fn default_manifest_path(year: u32) -> PathBuf {
+    input_dir(year).join("answers.toml")
+}
+
+const MANIFEST_HEADER: &str = "\
+# Known answers for the real inputs in this directory.
+# Checked by `cargo run -- verify` and `cargo test --features verify-answers`.
+";
+
+/// Known answers per day and part, for the real inputs.
+///
+/// Stored as TOML with one table per day:
+///
+/// ```toml
+/// [day5]
+/// part1 = "CMZ"
+/// part2 = 42
+/// ```
+///
+/// Answers are compared by their text, so bitmaps are multi-line strings of `#` and `.`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    answers: BTreeMap<(u32, Part), Known>,
+}
+
+/// A known answer, and whether it is written as a TOML integer rather than a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Known {
+    text: String,
+    number: bool,
+}
+
+/// The result of comparing an answer with the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Correct,
+    Wrong { expected: String },
+    Unknown,
+}
+
+impl Manifest {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        std::fs::read_to_string(path)
+            .with_context(|| format!("could not read {path:?}"))?
+            .parse()
+            .with_context(|| format!("bad answer manifest {path:?}"))
+    }
+
+    /// Like [`Manifest::load`], but empty if there is no file yet, as for a new year.
+    pub fn load_or_default(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(text) => text
+                .parse()
+                .with_context(|| format!("bad answer manifest {path:?}")),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("could not read {path:?}")),
+        }
+    }
+
+    /// Write the manifest, replacing any comments in an existing file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, format!("{MANIFEST_HEADER}\n{self}"))
+            .with_context(|| format!("could not write {path:?}"))
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&str> {
+        self.answers
+            .get(&(day, part))
+            .map(|known| known.text.as_str())
+    }
+
+    pub fn set(&mut self, day: u32, part: Part, answer: &Answer) {
+        let known = Known {
+            text: answer.to_string(),
+            number: matches!(answer, Answer::Number(_)),
+        };
+        self.answers.insert((day, part), known);
+    }
+
+    pub fn check(&self, day: u32, part: Part, answer: &Answer) -> Check {
+        check(self.get(day, part), answer)
+    }
+}
+
+fn parse_day(key: &str) -> Result<u32> {
+    key.strip_prefix("day")
+        .and_then(|d| d.parse().ok())
+        .ok_or_else(|| anyhow!("bad day {key:?} (expected `dayN`)"))
+}
+
+pub(crate) fn parse_part(key: &str) -> Result<Part> {
+    key.strip_prefix("part")
+        .ok_or_else(|| anyhow!("bad part {key:?} (expected `part1` or `part2`)"))?
+        .parse()
+}
+
+/// An answer as written in TOML, either an integer or a (possibly multi-line) string.
+pub(crate) fn parse_answer(value: toml::Value) -> Result<String> {
+    match value {
+        toml::Value::Integer(n) => Ok(n.to_string()),
+        toml::Value::String(s) => Ok(s.trim().to_string()),
+        other => Err(anyhow!("unsupported answer type {}", other.type_str())),
+    }
+}
+
+/// Compare an answer with the expected one, if any.
+pub(crate) fn check(expected: Option<&str>, answer: &Answer) -> Check {
+    match expected {
+        Some(expected) if expected == answer.to_string().trim() => Check::Correct,
+        Some(expected) => Check::Wrong {
+            expected: expected.to_string(),
+        },
+        None => Check::Unknown,
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table: toml::Table = s.parse()?;
+
+        let mut answers = BTreeMap::new();
+        for (day_key, parts) in table {
+            let day = parse_day(&day_key)?;
+            let toml::Value::Table(parts) = parts else {
+                Err(anyhow!("{day_key} is not a table"))?
+            };
+            for (part_key, value) in parts {
+                let part = parse_part(&part_key)?;
+                let number = value.is_integer();
+                let text = parse_answer(value)
+                    .with_context(|| format!("bad answer for {day_key}.{part_key}"))?;
+                answers.insert((day, part), Known { text, number });
+            }
+        }
+
+        Ok(Self { answers })
+    }
+}
+
+impl Display for Manifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut current_day = None;
+        for (&(day, part), Known { text, number }) in &self.answers {
+            if current_day != Some(day) {
+                if current_day.is_some() {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[day{day}]")?;
+                current_day = Some(day);
+            }
+            if *number {
+                writeln!(f, "part{part} = {text}")?;
+            } else if text.contains('\n') {
+                writeln!(f, "part{part} = \"\"\"\n{text}\n\"\"\"")?;
+            } else {
+                writeln!(f, "part{part} = {}", toml::Value::String(text.clone()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{default_manifest_path, Check, Manifest};
+    use crate::solution::{Answer, Part};
+    use anyhow::Result;
+
+    const MANIFEST: &str = r#"
+[day5]
+part1 = "CMZ"
+part2 = 12
+
+[day10]
+part2 = """
+#.#
+.#.
+"""
+"#;
+
+    #[test]
+    fn parse_and_check() -> Result<()> {
+        let manifest: Manifest = MANIFEST.parse()?;
+        assert_eq!(
+            manifest.check(5, Part::One, &Answer::Text("CMZ".into())),
+            Check::Correct
+        );
+        assert_eq!(
+            manifest.check(5, Part::Two, &Answer::Number(13)),
+            Check::Wrong {
+                expected: "12".into()
+            }
+        );
+        let bitmap = Answer::Bitmap {
+            width: 3,
+            pixels: vec![true, false, true, false, true, false],
+        };
+        assert_eq!(manifest.check(10, Part::Two, &bitmap), Check::Correct);
+        assert_eq!(manifest.check(10, Part::One, &bitmap), Check::Unknown);
+        Ok(())
+    }
+
+    #[test]
+    fn display_round_trip() -> Result<()> {
+        let manifest: Manifest = MANIFEST.parse()?;
+        assert_eq!(manifest.to_string().parse::<Manifest>()?, manifest);
+        Ok(())
+    }
+
+    #[test]
+    fn text_that_looks_like_a_number_stays_text() -> Result<()> {
+        let mut manifest = Manifest::default();
+        manifest.set(1, Part::One, &Answer::Text("007".into()));
+        manifest.set(1, Part::Two, &Answer::Number(7));
+        let text = manifest.to_string();
+        assert_eq!(text, "[day1]\npart1 = \"007\"\npart2 = 7\n");
+        assert_eq!(text.parse::<Manifest>()?, manifest);
+        Ok(())
+    }
+
+    #[test]
+    fn save_keeps_real_manifest_layout() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("answers.toml");
+        let manifest = Manifest::load(default_manifest_path(2022))?;
+        manifest.save(&path)?;
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            std::fs::read_to_string(default_manifest_path(2022))?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_bad_keys() {
+        assert!("[dayX]\npart1 = 1".parse::<Manifest>().is_err());
+        assert!("[day1]\npart3 = 1".parse::<Manifest>().is_err());
+        assert!("[day1]\npart1 = 1.5".parse::<Manifest>().is_err());
+    }
+}
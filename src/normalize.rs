@@ -0,0 +1,152 @@
+//! Cleaning up input before a generator sees it, so that files saved on any machine parse
+//! the same.
+//!
+//! Lines are never joined or removed, except empty ones at the end, so the line numbers of a
+//! [`ParseError`](crate::parse::ParseError) still match the file.
+
+use std::borrow::Cow;
+
+/// What happens to the whitespace around each line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Whitespace {
+    Keep,
+    /// Remove trailing whitespace, keeping the indentation.
+    TrimEnd,
+    Trim,
+}
+
+/// How the input of a day is cleaned up before its generator runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalize {
+    /// Strip a byte order mark, and the `\r` of `\r\n` line endings.
+    pub line_endings: bool,
+    pub whitespace: Whitespace,
+    /// Expand tabs to spaces, with tab stops this many columns apart.
+    pub tab_width: Option<usize>,
+    /// Remove lines at the end that are empty or only whitespace.
+    pub drop_trailing_empty_lines: bool,
+}
+
+impl Normalize {
+    /// The input exactly as it was read.
+    pub const RAW: Normalize = Normalize {
+        line_endings: false,
+        whitespace: Whitespace::Keep,
+        tab_width: None,
+        drop_trailing_empty_lines: false,
+    };
+
+    /// For inputs where only the words on each line matter.
+    pub const TRIM: Normalize = Normalize {
+        line_endings: true,
+        whitespace: Whitespace::Trim,
+        tab_width: None,
+        drop_trailing_empty_lines: true,
+    };
+
+    /// For inputs where columns matter, like day 5's crate drawing.
+    pub const INDENTED: Normalize = Normalize {
+        line_endings: true,
+        whitespace: Whitespace::TrimEnd,
+        tab_width: Some(4),
+        drop_trailing_empty_lines: true,
+    };
+
+    pub fn apply<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        if *self == Self::RAW {
+            return Cow::Borrowed(input);
+        }
+        let input = match self.line_endings {
+            true => input.strip_prefix('\u{feff}').unwrap_or(input),
+            false => input,
+        };
+
+        let mut out = String::with_capacity(input.len());
+        for raw in input.split_inclusive('\n') {
+            let (line, newline) = match raw.strip_suffix('\n') {
+                Some(line) => (line, "\n"),
+                // A final line without `\n` gets one, like every other line.
+                None if self.line_endings => (raw, "\n"),
+                None => (raw, ""),
+            };
+            self.push_line(&mut out, line);
+            out.push_str(newline);
+        }
+        if self.drop_trailing_empty_lines {
+            let end = match out.trim_end().len() {
+                0 => 0,
+                end => out[end..].find('\n').map_or(out.len(), |i| end + i + 1),
+            };
+            out.truncate(end);
+        }
+        Cow::Owned(out)
+    }
+
+    /// Append one line, without its `\n`, as [`Normalize::apply`] would.
+    pub fn push_line(&self, out: &mut String, mut line: &str) {
+        if self.line_endings {
+            line = line.strip_suffix('\r').unwrap_or(line);
+        }
+        let line = match self.whitespace {
+            Whitespace::Keep => line,
+            Whitespace::TrimEnd => line.trim_end(),
+            Whitespace::Trim => line.trim(),
+        };
+        match self.tab_width {
+            Some(width) if line.contains('\t') => expand_tabs(out, line, width),
+            _ => out.push_str(line),
+        }
+    }
+}
+
+fn expand_tabs(out: &mut String, line: &str, width: usize) {
+    let mut column = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = width - column % width;
+            out.extend(std::iter::repeat_n(' ', spaces));
+            column += spaces;
+        } else {
+            out.push(c);
+            column += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Normalize, Whitespace};
+
+    #[test]
+    fn raw_is_untouched() {
+        let input = "\u{feff} a\r\n\n";
+        assert_eq!(Normalize::RAW.apply(input), input);
+    }
+
+    #[test]
+    fn windows_files_parse_the_same() {
+        let unix = "1000\n2000\n\n3000\n";
+        let windows = "\u{feff}1000\r\n2000 \r\n\r\n\t3000\r\n\r\n";
+        assert_eq!(Normalize::TRIM.apply(windows), unix);
+        assert_eq!(Normalize::TRIM.apply("1000\n2000\n\n3000"), unix);
+    }
+
+    #[test]
+    fn indentation_is_kept() {
+        let input = "    [D]    \r\n[N]\t[C]\n 1   2   3 \n\n\n";
+        assert_eq!(
+            Normalize::INDENTED.apply(input),
+            "    [D]\n[N] [C]\n 1   2   3\n"
+        );
+    }
+
+    #[test]
+    fn leading_empty_lines_are_kept() {
+        let policy = Normalize {
+            whitespace: Whitespace::Keep,
+            ..Normalize::TRIM
+        };
+        assert_eq!(policy.apply("\n \nnoop \n \n"), "\n \nnoop \n");
+        assert_eq!(policy.apply(" \n\n"), "");
+    }
+}
@@ -0,0 +1,404 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+/// Why and where a generator rejected its input.
+///
+/// Lines and columns are 1-based and count characters. `text` is the whole offending line,
+/// so the error can be shown with the line underneath.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Filled in when the error leaves the day's generator.
+    pub day: Option<u32>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    /// An error on the first line of `line`, where `found` describes what is at `column`.
+    pub fn new(
+        line: &str,
+        column: usize,
+        expected: impl Into<String>,
+        found: impl Into<String>,
+    ) -> Self {
+        Self {
+            day: None,
+            line: 1,
+            column,
+            text: line.to_string(),
+            expected: expected.into(),
+            found: found.into(),
+        }
+    }
+
+    /// An error at the bytes `span` of `line`, which is what was found there.
+    pub fn at(line: &str, span: Range<usize>, expected: impl Into<String>) -> Self {
+        let token = &line[span.clone()];
+        let found = if token.is_empty() {
+            "end of line".to_string()
+        } else {
+            format!("{token:?}")
+        };
+        let column = line[..span.start].chars().count() + 1;
+        Self::new(line, column, expected, found)
+    }
+
+    /// Move the error down by `lines`, for errors from a line or block further into the input.
+    pub fn offset_lines(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+
+    pub fn with_day(mut self, day: u32) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    /// Show the error like a compiler would: the location in `source_name`, and the
+    /// offending line between its neighbours in `input`, with a caret under the column.
+    ///
+    /// The offending line is shown as the generator saw it, after any
+    /// [normalization](crate::normalize), so the caret lines up.
+    pub fn render(&self, source_name: &str, input: &str) -> String {
+        let lines = input.lines().collect::<Vec<_>>();
+        let first = self.line.saturating_sub(CONTEXT_LINES).max(1);
+        let last = (self.line + CONTEXT_LINES).min(lines.len().max(self.line));
+        let gutter = last.to_string().len();
+        let line_text = |number: usize| match lines.get(number - 1) {
+            _ if number == self.line => self.text.as_str(),
+            Some(text) => *text,
+            None => "",
+        };
+
+        let mut out = format!("error: expected {}, found {}\n", self.expected, self.found);
+        out += &format!(
+            "{:gutter$}--> {source_name}:{}:{}\n",
+            "", self.line, self.column
+        );
+        out += &format!("{:gutter$} |\n", "");
+        for number in first..=last {
+            let text = line_text(number);
+            out += format!("{number:>gutter$} | {text}").trim_end();
+            out += "\n";
+            if number == self.line {
+                // Keep tabs, so the caret lines up however wide they are shown.
+                let indent = text
+                    .chars()
+                    .take(self.column - 1)
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect::<String>();
+                out += &format!("{:gutter$} | {indent}^ expected {}\n", "", self.expected);
+            }
+        }
+        out += &format!("{:gutter$} |\n", "");
+        out
+    }
+}
+
+/// How many lines to show before and after the offending one in [`ParseError::render`].
+const CONTEXT_LINES: usize = 2;
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day}, ")?;
+        }
+        write!(
+            f,
+            "line {}, column {}: expected {}, found {}",
+            self.line, self.column, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The bytes of `line` without the whitespace around them. An empty span at the end for a
+/// line of nothing but whitespace.
+pub fn trimmed(line: &str) -> Range<usize> {
+    let start = line.len() - line.trim_start().len();
+    start..line.trim_end().len().max(start)
+}
+
+/// Parse the bytes `span` of `line` as a number.
+pub fn number<T: FromStr>(line: &str, span: Range<usize>) -> Result<T, ParseError> {
+    line[span.clone()]
+        .parse()
+        .map_err(|_| ParseError::at(line, span, "a number"))
+}
+
+/// A block of lines between blank lines, like the calories of an elf in day 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Record<'a> {
+    /// The 0-based index of the first line in the input.
+    pub first_line: usize,
+    /// The lines, from the start of the first to the end of the last without its `\n`.
+    pub text: &'a str,
+}
+
+impl<'a> Record<'a> {
+    /// The lines with their 0-based index in the input.
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> {
+        (self.first_line..).zip(self.text.lines())
+    }
+
+    /// Parse the record with its [`FromStr`], moving errors to the record's place in the
+    /// input.
+    pub fn parse<T: FromStr<Err = ParseError>>(&self) -> Result<T, ParseError> {
+        self.text
+            .parse()
+            .map_err(|e: ParseError| e.offset_lines(self.first_line))
+    }
+}
+
+/// The records of `input`, which are separated by lines that are empty or only whitespace.
+///
+/// Blank lines before the first record, after the last or several in a row are all the same
+/// as one: no record is ever empty.
+pub fn records(input: &str) -> impl Iterator<Item = Record<'_>> {
+    let mut offset = 0;
+    let mut lines = input
+        .split_inclusive('\n')
+        .enumerate()
+        .map(move |(index, raw)| {
+            let start = offset;
+            offset += raw.len();
+            let line = raw
+                .strip_suffix('\n')
+                .map_or(raw, |line| line.strip_suffix('\r').unwrap_or(line));
+            (index, start, line)
+        })
+        .peekable();
+
+    std::iter::from_fn(move || {
+        let (first_line, start, line) = lines.find(|(_, _, line)| !line.trim().is_empty())?;
+        let mut end = start + line.len();
+        while let Some((_, start, line)) = lines.next_if(|(_, _, line)| !line.trim().is_empty()) {
+            end = start + line.len();
+        }
+        Some(Record {
+            first_line,
+            text: &input[start..end],
+        })
+    })
+}
+
+/// Parse every record of `input` with `T`'s [`FromStr`].
+pub fn parse_records<T: FromStr<Err = ParseError>>(input: &str) -> Result<Vec<T>, ParseError> {
+    records(input).map(|record| record.parse()).collect()
+}
+
+/// Whitespace-separated words of a line, with errors pointing at the offending word.
+pub struct Words<'a> {
+    line: &'a str,
+    /// The byte offset of what hasn't been read yet.
+    pos: usize,
+    last: Range<usize>,
+}
+
+impl<'a> Words<'a> {
+    pub fn new(line: &'a str) -> Self {
+        Self {
+            line,
+            pos: 0,
+            last: 0..0,
+        }
+    }
+
+    pub fn line(&self) -> &'a str {
+        self.line
+    }
+
+    /// The word returned by the last call to [`Words::next`] or [`Words::rest`].
+    pub fn last(&self) -> &'a str {
+        &self.line[self.last.clone()]
+    }
+
+    /// Where [`Words::last`] is in the line, for errors about it.
+    pub fn last_span(&self) -> Range<usize> {
+        self.last.clone()
+    }
+
+    /// The span of what hasn't been read yet, without surrounding whitespace, which is all
+    /// read once this returns.
+    fn take_rest(&mut self) -> Range<usize> {
+        let rest = trimmed(&self.line[self.pos..]);
+        let span = self.pos + rest.start..self.pos + rest.end;
+        self.pos = self.line.len();
+        span
+    }
+
+    pub fn next(&mut self, expected: &str) -> Result<&'a str, ParseError> {
+        let rest = &self.line[self.pos..];
+        let start = self.pos + rest.len() - rest.trim_start().len();
+        let word_length = self.line[start..]
+            .find(char::is_whitespace)
+            .unwrap_or(self.line.len() - start);
+        let span = start..start + word_length;
+        self.pos = span.end;
+        if span.is_empty() {
+            Err(ParseError::at(self.line, span.clone(), expected))?;
+        }
+        self.last = span;
+        Ok(self.last())
+    }
+
+    pub fn keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        let expected = format!("`{keyword}`");
+        let word = self.next(&expected)?;
+        if word != keyword {
+            Err(ParseError::at(self.line, self.last_span(), expected))?;
+        }
+        Ok(())
+    }
+
+    pub fn number<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        self.next("a number")?;
+        number(self.line, self.last_span())
+    }
+
+    /// Everything after the words read so far, without surrounding whitespace.
+    pub fn rest(&mut self, expected: &str) -> Result<&'a str, ParseError> {
+        let span = self.take_rest();
+        if span.is_empty() {
+            Err(ParseError::at(self.line, span.clone(), expected))?;
+        }
+        self.last = span;
+        Ok(self.last())
+    }
+
+    /// Like [`Words::rest`], for lines where nothing may follow.
+    pub fn rest_or_empty(&mut self) -> &'a str {
+        self.last = self.take_rest();
+        self.last()
+    }
+
+    pub fn end(&mut self) -> Result<(), ParseError> {
+        let span = self.take_rest();
+        if !span.is_empty() {
+            Err(ParseError::at(self.line, span, "end of line"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{number, parse_records, records, trimmed, ParseError, Record, Words};
+    use anyhow::Result;
+
+    #[test]
+    fn words_point_at_columns() -> Result<()> {
+        let line = "  move 1 from x to 3";
+        let mut words = Words::new(line);
+        words.keyword("move")?;
+        assert_eq!(words.number::<usize>()?, 1);
+        words.keyword("from")?;
+        let error = words.number::<usize>().unwrap_err();
+        assert_eq!((error.line, error.column), (1, 15));
+        assert_eq!(error.found, "\"x\"");
+        assert_eq!(error.text, line);
+
+        let error = words.end().unwrap_err();
+        assert_eq!(error.column, 17);
+        assert_eq!(error.expected, "end of line");
+        Ok(())
+    }
+
+    #[test]
+    fn end_of_line() {
+        let mut words = Words::new("noop");
+        assert!(words.keyword("noop").is_ok());
+        let error = words.number::<i32>().unwrap_err();
+        assert_eq!(error.column, 5);
+        assert_eq!(error.found, "end of line");
+    }
+
+    #[test]
+    fn trimmed_spans() {
+        assert_eq!(trimmed("  12 "), 2..4);
+        assert_eq!(trimmed("12"), 0..2);
+        assert_eq!(trimmed("   "), 3..3);
+        let error = number::<u8>(" 1x ", trimmed(" 1x ")).unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (2, "\"1x\""));
+    }
+
+    #[test]
+    fn records_between_blank_lines() {
+        let input = "\n  \na\nb\n\n\n \nc\r\n\n";
+        let found = records(input).collect::<Vec<_>>();
+        let record = |first_line, text| Record { first_line, text };
+        assert_eq!(found, [record(2, "a\nb"), record(7, "c")]);
+        assert_eq!(found[0].lines().collect::<Vec<_>>(), [(2, "a"), (3, "b")]);
+        assert_eq!(records("a").collect::<Vec<_>>(), [record(0, "a")]);
+        assert_eq!(records("").count(), 0);
+        assert_eq!(records("\n \n").count(), 0);
+    }
+
+    #[test]
+    fn record_errors_are_moved() {
+        #[derive(Debug)]
+        struct Pair;
+        impl std::str::FromStr for Pair {
+            type Err = ParseError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let second = s.lines().nth(1).unwrap_or_default();
+                Err(ParseError::at(second, 0..second.len(), "more").offset_lines(1))
+            }
+        }
+        let error = parse_records::<Pair>("\n\nx\ny\n").unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (4, "y"));
+    }
+
+    #[test]
+    fn render() {
+        let input = "move 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 3 from x to 2\nmove 1 from 1 to 2\n";
+        let line = input.lines().nth(3).unwrap_or_default();
+        let error = ParseError::at(line, 12..13, "a number").offset_lines(3);
+        assert_eq!(
+            error.render("day5.txt", input),
+            "\
+error: expected a number, found \"x\"
+ --> day5.txt:4:13
+  |
+2 | move 3 from 1 to 3
+3 | move 2 from 2 to 1
+4 | move 3 from x to 2
+  |             ^ expected a number
+5 | move 1 from 1 to 2
+  |
+"
+        );
+    }
+
+    #[test]
+    fn render_end_of_input() {
+        let error = ParseError::new("", 1, "a row of trees", "end of input").offset_lines(1);
+        assert_eq!(
+            error.render("-", "\n"),
+            "\
+error: expected a row of trees, found end of input
+ --> -:2:1
+  |
+1 |
+2 |
+  | ^ expected a row of trees
+  |
+"
+        );
+    }
+
+    #[test]
+    fn display() {
+        let line = "1-2,x-4";
+        let error = ParseError::at(line, 4..5, "a number")
+            .offset_lines(2)
+            .with_day(4);
+        assert_eq!(
+            error.to_string(),
+            "day 4, line 3, column 5: expected a number, found \"x\""
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::OnceLock;
+
+use itertools::Itertools;
+
+use crate::{solution::Solution, DEFAULT_YEAR};
+
+/// All implemented days, ordered by year and day.
+pub struct Registry {
+    solutions: Vec<Box<dyn Solution>>,
+}
+
+impl Registry {
+    fn new() -> Self {
+        let mut solutions = vec![
+            crate::day1::solution(),
+            crate::day2::solution(),
+            crate::day3::solution(),
+            crate::day4::solution(),
+            crate::day5::solution(),
+            crate::day6::solution(),
+            crate::day7::solution(),
+            crate::day8::solution(),
+            crate::day9::solution(),
+            crate::day10::solution(),
+            crate::day11::solution(),
+        ];
+        solutions.sort_by_key(|s| (s.year(), s.day()));
+        Self { solutions }
+    }
+
+    /// A day of [`DEFAULT_YEAR`].
+    pub fn get(&self, day: u32) -> Option<&dyn Solution> {
+        self.get_in(DEFAULT_YEAR, day)
+    }
+
+    pub fn get_in(&self, year: u32, day: u32) -> Option<&dyn Solution> {
+        self.year(year).get(day)
+    }
+
+    /// Every day of every year.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Solution> {
+        self.solutions.iter().map(|s| s.as_ref())
+    }
+
+    /// The years with at least one day.
+    pub fn years(&self) -> impl Iterator<Item = u32> + '_ {
+        self.iter().map(|s| s.year()).dedup()
+    }
+
+    /// The days of `year`, which are none for a year that hasn't started.
+    pub fn year(&self, year: u32) -> Year<'_> {
+        let start = self.solutions.partition_point(|s| s.year() < year);
+        let end = self.solutions.partition_point(|s| s.year() <= year);
+        Year {
+            year,
+            solutions: &self.solutions[start..end],
+        }
+    }
+}
+
+/// The implemented days of one year, ordered by day.
+#[derive(Clone, Copy)]
+pub struct Year<'a> {
+    year: u32,
+    solutions: &'a [Box<dyn Solution>],
+}
+
+impl<'a> Year<'a> {
+    pub fn year(&self) -> u32 {
+        self.year
+    }
+
+    pub fn get(&self, day: u32) -> Option<&'a dyn Solution> {
+        self.iter().find(|s| s.day() == day)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a dyn Solution> {
+        self.solutions.iter().map(|s| s.as_ref())
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = u32> + 'a {
+        self.iter().map(|s| s.day())
+    }
+}
+
+pub fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Registry::new)
+}
+
+#[cfg(test)]
+mod test {
+    use super::registry;
+    use crate::solution::{Answer, Part};
+    use anyhow::Result;
+
+    #[test]
+    fn lists_all_days() {
+        // Scaffolding adds days, so only check the ones that were there from the start.
+        let days = registry().year(2022).days().collect::<Vec<_>>();
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]), "{days:?}");
+        assert!((1..=11).all(|day| days.contains(&day)), "{days:?}");
+        assert!(registry().years().any(|year| year == 2022));
+        assert_eq!(registry().year(2014).days().count(), 0);
+        assert!(registry().get_in(2014, 1).is_none());
+        assert_eq!(registry().get_in(2022, 9).map(|s| s.day()), Some(9));
+    }
+
+    #[test]
+    fn part2_through_registry() -> Result<()> {
+        let input = include_str!("../input/2022/day9.example2.txt");
+        let day9 = registry().get(9).expect("day 9 is registered");
+        assert_eq!(day9.part2(input)?, Answer::Number(36));
+        Ok(())
+    }
+
+    #[test]
+    fn named_variants() -> Result<()> {
+        let day6 = registry().get(6).expect("day 6 is registered");
+        let data = day6.parse("mjqjpqmgbljsphdztnvjfqwrcgsmlb")?;
+        for name in ["A_Tuples", "B_Generic"] {
+            let variant = day6.find_variant(Part::One, Some(name))?;
+            assert_eq!(day6.solve(&data, variant)?, Answer::Number(7));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,379 @@
+use std::{io::Write, str::FromStr, time::Duration};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    allocations::AllocStats,
+    manifest::{Check, Manifest},
+    runner::DayRun,
+    solution::{Answer, Solution, Variant},
+};
+
+/// How the runner prints its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// For people, as the runner always did.
+    #[default]
+    Text,
+    /// One JSON object per line and solver.
+    Json,
+    /// A header line, then one line per solver.
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(anyhow!("bad format {s:?} (expected text, json or csv)")),
+        }
+    }
+}
+
+/// An [`Answer`] as it is written out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AnswerValue {
+    Number {
+        value: i64,
+    },
+    Text {
+        value: String,
+    },
+    /// Rows of `#` and `.`, top to bottom.
+    Bitmap {
+        width: usize,
+        height: usize,
+        rows: Vec<String>,
+    },
+}
+
+impl From<&Answer> for AnswerValue {
+    fn from(answer: &Answer) -> Self {
+        match answer {
+            Answer::Number(value) => AnswerValue::Number { value: *value },
+            Answer::Text(value) => AnswerValue::Text {
+                value: value.clone(),
+            },
+            Answer::Bitmap { width, .. } => {
+                let rows = answer
+                    .to_string()
+                    .lines()
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                AnswerValue::Bitmap {
+                    width: *width,
+                    height: rows.len(),
+                    rows,
+                }
+            }
+        }
+    }
+}
+
+/// The result of one solver on one input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Record {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub variant: Option<&'static str>,
+    pub answer: Option<AnswerValue>,
+    /// Why there is no answer, either from the generator or the solver.
+    pub error: Option<String>,
+    pub parse_ns: u64,
+    /// Missing when the generator failed and the solver never ran.
+    pub solve_ns: Option<u64>,
+    /// The allocations of the generator and solver, only counted with the
+    /// `count-allocations` feature.
+    pub parse_allocations: Option<u64>,
+    pub parse_allocated_bytes: Option<u64>,
+    pub parse_peak_bytes: Option<u64>,
+    pub solve_allocations: Option<u64>,
+    pub solve_allocated_bytes: Option<u64>,
+    pub solve_peak_bytes: Option<u64>,
+    /// SHA-256 of the input, in hex.
+    pub input_sha256: String,
+    /// How the answer compares with the answer manifest, when it was checked.
+    pub check: Option<&'static str>,
+}
+
+/// The flat form of a [`Record`], as CSV has no nesting.
+#[derive(Serialize)]
+struct CsvRecord<'a> {
+    year: u32,
+    day: u32,
+    part: u8,
+    variant: Option<&'static str>,
+    kind: Option<&'static str>,
+    /// Bitmap rows are joined with newlines.
+    answer: Option<String>,
+    error: Option<&'a str>,
+    parse_ns: u64,
+    solve_ns: Option<u64>,
+    parse_allocations: Option<u64>,
+    parse_allocated_bytes: Option<u64>,
+    parse_peak_bytes: Option<u64>,
+    solve_allocations: Option<u64>,
+    solve_allocated_bytes: Option<u64>,
+    solve_peak_bytes: Option<u64>,
+    input_sha256: &'a str,
+    check: Option<&'static str>,
+}
+
+impl<'a> From<&'a Record> for CsvRecord<'a> {
+    fn from(record: &'a Record) -> Self {
+        let (kind, answer) = match &record.answer {
+            Some(AnswerValue::Number { value }) => (Some("number"), Some(value.to_string())),
+            Some(AnswerValue::Text { value }) => (Some("text"), Some(value.clone())),
+            Some(AnswerValue::Bitmap { rows, .. }) => (Some("bitmap"), Some(rows.join("\n"))),
+            None => (None, None),
+        };
+        Self {
+            year: record.year,
+            day: record.day,
+            part: record.part,
+            variant: record.variant,
+            kind,
+            answer,
+            error: record.error.as_deref(),
+            parse_ns: record.parse_ns,
+            solve_ns: record.solve_ns,
+            parse_allocations: record.parse_allocations,
+            parse_allocated_bytes: record.parse_allocated_bytes,
+            parse_peak_bytes: record.parse_peak_bytes,
+            solve_allocations: record.solve_allocations,
+            solve_allocated_bytes: record.solve_allocated_bytes,
+            solve_peak_bytes: record.solve_peak_bytes,
+            input_sha256: &record.input_sha256,
+            check: record.check,
+        }
+    }
+}
+
+pub fn input_hash(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+fn check_name(check: &Check) -> &'static str {
+    match check {
+        Check::Correct => "correct",
+        Check::Wrong { .. } => "wrong",
+        Check::Unknown => "unknown",
+    }
+}
+
+/// One record per solver of `run`, in the order they ran, with answers checked against
+/// `manifest` if given. When the generator failed, every solver accepted by `filter` gets a
+/// record with the generator's error.
+pub fn records(
+    solution: &dyn Solution,
+    run: &DayRun,
+    input: &str,
+    filter: impl Fn(&Variant) -> bool,
+    manifest: Option<&Manifest>,
+) -> Vec<Record> {
+    let input_sha256 = input_hash(input);
+    let parse_ns = nanos(run.parse_time);
+    let parse = run.parse_allocations;
+    let record = |variant: Variant| Record {
+        year: run.year,
+        day: run.day,
+        part: variant.part.number(),
+        variant: variant.name,
+        answer: None,
+        error: None,
+        parse_ns,
+        solve_ns: None,
+        parse_allocations: parse.map(|a| a.count),
+        parse_allocated_bytes: parse.map(|a| a.bytes),
+        parse_peak_bytes: parse.map(|a| a.peak),
+        solve_allocations: None,
+        solve_allocated_bytes: None,
+        solve_peak_bytes: None,
+        input_sha256: input_sha256.clone(),
+        check: None,
+    };
+
+    match &run.parts {
+        Ok(parts) => parts
+            .iter()
+            .map(|part| {
+                let solved = Record {
+                    solve_ns: Some(nanos(part.elapsed)),
+                    ..with_solve_allocations(record(part.variant), part.allocations)
+                };
+                match &part.answer {
+                    Ok(answer) => Record {
+                        answer: Some(answer.into()),
+                        check: manifest
+                            .map(|m| check_name(&m.check(run.day, part.variant.part, answer))),
+                        ..solved
+                    },
+                    Err(e) => Record {
+                        error: Some(format!("{e:#}")),
+                        ..solved
+                    },
+                }
+            })
+            .collect(),
+        Err(e) => solution
+            .variants()
+            .into_iter()
+            .filter(|v| filter(v))
+            .map(|variant| Record {
+                error: Some(format!("generator failed: {e:#}")),
+                ..record(variant)
+            })
+            .collect(),
+    }
+}
+
+fn with_solve_allocations(record: Record, allocations: Option<AllocStats>) -> Record {
+    Record {
+        solve_allocations: allocations.map(|a| a.count),
+        solve_allocated_bytes: allocations.map(|a| a.bytes),
+        solve_peak_bytes: allocations.map(|a| a.peak),
+        ..record
+    }
+}
+
+/// Writes records as they come, in JSON lines or CSV.
+pub enum Writer<W: Write> {
+    Json(W),
+    Csv(Box<csv::Writer<W>>),
+}
+
+impl<W: Write> Writer<W> {
+    /// Fails for [`Format::Text`], which is for people and printed by the runner itself.
+    pub fn new(format: Format, out: W) -> Result<Self> {
+        match format {
+            Format::Text => Err(anyhow!("records are only written as json or csv")),
+            Format::Json => Ok(Writer::Json(out)),
+            Format::Csv => Ok(Writer::Csv(Box::new(csv::Writer::from_writer(out)))),
+        }
+    }
+
+    pub fn write(&mut self, record: &Record) -> Result<()> {
+        match self {
+            Writer::Json(out) => {
+                serde_json::to_writer(&mut *out, record)?;
+                writeln!(out)?;
+            }
+            Writer::Csv(out) => out.serialize(CsvRecord::from(record))?,
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        match self {
+            Writer::Json(out) => out.flush()?,
+            Writer::Csv(out) => out.flush()?,
+        }
+        Ok(())
+    }
+
+    /// Flush and return the output.
+    pub fn into_inner(self) -> Result<W> {
+        match self {
+            Writer::Json(mut out) => {
+                out.flush()?;
+                Ok(out)
+            }
+            Writer::Csv(out) => out.into_inner().map_err(|e| anyhow!("{}", e.error())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{input_hash, records, Format, Writer};
+    use crate::{manifest::Manifest, registry, runner::run_day, Answer, Part};
+    use anyhow::Result;
+
+    #[test]
+    fn answers_are_tagged() -> Result<()> {
+        let bitmap = Answer::Bitmap {
+            width: 2,
+            pixels: vec![true, false, false, true],
+        };
+        assert_eq!(
+            serde_json::to_string(&super::AnswerValue::from(&bitmap))?,
+            r##"{"type":"bitmap","width":2,"height":2,"rows":["#.",".#"]}"##
+        );
+        assert_eq!(
+            serde_json::to_string(&super::AnswerValue::from(&Answer::Text("CMZ".into())))?,
+            r#"{"type":"text","value":"CMZ"}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn json_lines_and_csv() -> Result<()> {
+        let input = include_str!("../input/2022/day5.example.txt");
+        let solution = registry().get(5).expect("day 5 is implemented");
+        let run = run_day(solution, input, |_| true);
+        let manifest = "[day5]\npart1 = \"CMZ\"\npart2 = \"XYZ\"\n".parse::<Manifest>()?;
+        let records = records(solution, &run, input, |_| true, Some(&manifest));
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].check, Some("correct"));
+        assert_eq!(records[1].check, Some("wrong"));
+        assert_eq!(records[0].input_sha256, input_hash(input));
+        assert_eq!(records[0].input_sha256.len(), 64);
+
+        let mut json = Writer::new(Format::Json, vec![])?;
+        let mut csv = Writer::new(Format::Csv, vec![])?;
+        for record in &records {
+            json.write(record)?;
+            csv.write(record)?;
+        }
+
+        let json = String::from_utf8(json.into_inner()?)?;
+        let lines = json.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        let first: serde_json::Value = serde_json::from_str(lines[0])?;
+        assert_eq!(first["year"], 2022);
+        assert_eq!(first["day"], 5);
+        assert_eq!(first["answer"]["value"], "CMZ");
+        assert_eq!(first["error"], serde_json::Value::Null);
+
+        let csv = String::from_utf8(csv.into_inner()?)?;
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some(
+                "year,day,part,variant,kind,answer,error,parse_ns,solve_ns,\
+                parse_allocations,parse_allocated_bytes,parse_peak_bytes,\
+                solve_allocations,solve_allocated_bytes,solve_peak_bytes,input_sha256,check"
+            )
+        );
+        assert!(lines
+            .next()
+            .is_some_and(|l| l.starts_with("2022,5,1,,text,CMZ,,")));
+        assert!(lines.next().is_some_and(|l| l.ends_with(",wrong")));
+        Ok(())
+    }
+
+    #[test]
+    fn generator_errors_are_recorded() {
+        let input = "nonsense\n";
+        let solution = registry().get(9).expect("day 9 is implemented");
+        let run = run_day(solution, input, |_| true);
+        let records = records(solution, &run, input, |v| v.part == Part::Two, None);
+        assert_eq!(records.len(), 1);
+        assert!(records[0].error.is_some() && records[0].solve_ns.is_none());
+    }
+}
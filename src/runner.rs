@@ -0,0 +1,260 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+use rayon::prelude::*;
+
+use crate::{
+    allocations::{measure, AllocStats},
+    solution::{Answer, Parsed, Solution, Variant},
+};
+
+/// Where the inputs, examples and known answers of `year` live, relative to the repository
+/// root.
+pub fn input_dir(year: u32) -> PathBuf {
+    PathBuf::from(format!("input/{year}"))
+}
+
+/// Where the real input for `day` lives, relative to the repository root.
+pub fn default_input_path(year: u32, day: u32) -> PathBuf {
+    input_dir(year).join(format!("day{day}.txt"))
+}
+
+/// Read an input file, where `-` means stdin.
+pub fn read_input(path: &Path) -> Result<String> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .context("could not read stdin")?;
+        Ok(input)
+    } else {
+        std::fs::read_to_string(path).with_context(|| format!("could not read {path:?}"))
+    }
+}
+
+/// Open an input file for reading bit by bit, where `-` means stdin.
+pub fn open_input(path: &Path) -> Result<Box<dyn BufRead>> {
+    if path == Path::new("-") {
+        Ok(Box::new(std::io::stdin().lock()))
+    } else {
+        let file = std::fs::File::open(path).with_context(|| format!("could not open {path:?}"))?;
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// The days of an Advent of Code calendar.
+const DAYS: RangeInclusive<u32> = 1..=25;
+
+/// A set of days picked on the command line, like `5`, `1-5`, `1,3,7-9` or `all`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DaySelection {
+    All,
+    Days(Vec<u32>),
+}
+
+impl DaySelection {
+    pub fn contains(&self, day: u32) -> bool {
+        match self {
+            DaySelection::All => true,
+            DaySelection::Days(days) => days.contains(&day),
+        }
+    }
+}
+
+impl FromStr for DaySelection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(DaySelection::All);
+        }
+
+        let day = |text: &str| -> Result<u32> {
+            let day = text.trim().parse()?;
+            if !DAYS.contains(&day) {
+                Err(anyhow!("no day {day}, days go from 1 to 25"))?;
+            }
+            Ok(day)
+        };
+        let mut days = vec![];
+        for item in s.split(',') {
+            let item = item.trim();
+            match item.split_once('-') {
+                Some((first, last)) => {
+                    let (first, last) = (day(first)?, day(last)?);
+                    if first > last {
+                        Err(anyhow!("empty day range {item:?}"))?;
+                    }
+                    days.extend(first..=last);
+                }
+                None => days.push(day(item)?),
+            }
+        }
+        days.sort_unstable();
+        days.dedup();
+        Ok(DaySelection::Days(days))
+    }
+}
+
+/// The outcome of running one solver on already parsed data.
+#[derive(Debug)]
+pub struct PartRun {
+    pub variant: Variant,
+    pub answer: Result<Answer>,
+    pub elapsed: Duration,
+    /// Only counted with the `count-allocations` feature.
+    pub allocations: Option<AllocStats>,
+}
+
+/// The outcome of parsing one input and running the selected solvers on it.
+#[derive(Debug)]
+pub struct DayRun {
+    pub year: u32,
+    pub day: u32,
+    pub parse_time: Duration,
+    /// Only counted with the `count-allocations` feature.
+    pub parse_allocations: Option<AllocStats>,
+    /// Only `Err` when the generator failed, in which case no parts were run.
+    pub parts: Result<Vec<PartRun>>,
+}
+
+impl DayRun {
+    pub fn is_ok(&self) -> bool {
+        match &self.parts {
+            Ok(parts) => parts.iter().all(|p| p.answer.is_ok()),
+            Err(_) => false,
+        }
+    }
+}
+
+fn run_part(solution: &dyn Solution, data: &Parsed, variant: Variant) -> PartRun {
+    let ((answer, elapsed), allocations) = measure(|| {
+        let start_time = Instant::now();
+        let answer = solution.solve(data, variant);
+        (answer, start_time.elapsed())
+    });
+    PartRun {
+        variant,
+        answer,
+        elapsed,
+        allocations,
+    }
+}
+
+/// Run the generator, timing it and counting its allocations.
+fn parse(solution: &dyn Solution, input: &str) -> (Result<Parsed>, Duration, Option<AllocStats>) {
+    let ((data, parse_time), allocations) = measure(|| {
+        let start_time = Instant::now();
+        let data = solution.parse(input);
+        (data, start_time.elapsed())
+    });
+    (data, parse_time, allocations)
+}
+
+/// Parse `input` once and run every variant accepted by `filter` on it.
+pub fn run_day(solution: &dyn Solution, input: &str, filter: impl Fn(&Variant) -> bool) -> DayRun {
+    let (data, parse_time, parse_allocations) = parse(solution, input);
+
+    let parts = data.map(|data| {
+        solution
+            .variants()
+            .into_iter()
+            .filter(|v| filter(v))
+            .map(|variant| run_part(solution, &data, variant))
+            .collect()
+    });
+
+    DayRun {
+        year: solution.year(),
+        day: solution.day(),
+        parse_time,
+        parse_allocations,
+        parts,
+    }
+}
+
+/// Like [`run_day`] for many days at once, with every day parsed and every variant solved
+/// on rayon's thread pool. The runs are returned in the order of `days`.
+pub fn run_days_parallel(
+    days: &[(&dyn Solution, &str)],
+    filter: impl Fn(&Variant) -> bool + Sync,
+) -> Vec<DayRun> {
+    days.par_iter()
+        .map(|&(solution, input)| {
+            let (data, parse_time, parse_allocations) = parse(solution, input);
+
+            let parts = data.map(|data| {
+                solution
+                    .variants()
+                    .into_par_iter()
+                    .filter(|v| filter(v))
+                    .map(|variant| run_part(solution, &data, variant))
+                    .collect()
+            });
+
+            DayRun {
+                year: solution.year(),
+                day: solution.day(),
+                parse_time,
+                parse_allocations,
+                parts,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{run_day, run_days_parallel, DaySelection, PartRun};
+    use crate::registry;
+    use anyhow::Result;
+
+    #[test]
+    fn day_selection() -> Result<()> {
+        assert_eq!("all".parse::<DaySelection>()?, DaySelection::All);
+        assert_eq!("7".parse::<DaySelection>()?, DaySelection::Days(vec![7]));
+        assert_eq!(
+            "9,1-3,2".parse::<DaySelection>()?,
+            DaySelection::Days(vec![1, 2, 3, 9])
+        );
+        assert!("3-1".parse::<DaySelection>().is_err());
+        assert!("x".parse::<DaySelection>().is_err());
+        assert!("0".parse::<DaySelection>().is_err());
+        assert!("1-4000000000".parse::<DaySelection>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parallel_runs_match_sequential_runs() -> Result<()> {
+        let inputs = [
+            (6, include_str!("../input/2022/day6.example.txt")),
+            (9, include_str!("../input/2022/day9.example.txt")),
+            (10, include_str!("../input/2022/day10.example.txt")),
+        ];
+        let days = inputs
+            .iter()
+            .map(|&(day, input)| (registry().get(day).expect("day is implemented"), input))
+            .collect::<Vec<_>>();
+
+        let runs = run_days_parallel(&days, |_| true);
+        assert_eq!(runs.len(), days.len());
+        for ((solution, input), run) in days.iter().zip(runs) {
+            let expected = run_day(*solution, input, |_| true);
+            assert_eq!(run.day, expected.day);
+            let answers = |parts: Result<Vec<PartRun>>| -> Result<Vec<_>> {
+                parts?
+                    .into_iter()
+                    .map(|p| Ok((p.variant, p.answer?)))
+                    .collect()
+            };
+            assert_eq!(answers(run.parts)?, answers(expected.parts)?);
+        }
+        Ok(())
+    }
+}
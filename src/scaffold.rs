@@ -0,0 +1,349 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::DEFAULT_YEAR;
+
+/// The module every new day starts from. Its `dayX`, `X` and `YEAR` placeholders are
+/// replaced.
+const TEMPLATE_PATH: &str = "src/dayX.rs";
+
+/// Expected answers for the new example, checked by `tests/examples.rs` once filled in.
+const EXPECTED_TEMPLATE: &str = "# part1 = 0\n# part2 = 0\n";
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("could not read {path:?}"))
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    std::fs::write(path, contents).with_context(|| format!("could not write {path:?}"))
+}
+
+/// Insert `pub mod {name};` among the other `pub mod` lines of `lib.rs` (or a year's
+/// `mod.rs`), keeping them sorted.
+fn add_module(lib: &str, name: &str) -> Result<String> {
+    let new_line = format!("pub mod {name};");
+    let mut lines = lib.lines().collect::<Vec<_>>();
+
+    let modules = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.starts_with("pub mod "))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let (&first, &last) = modules
+        .first()
+        .zip(modules.last())
+        .ok_or_else(|| anyhow!("no `pub mod` lines in lib.rs"))?;
+
+    let index = (first..=last)
+        .find(|&i| {
+            lines[i]
+                .trim_start_matches("pub mod ")
+                .trim_end_matches(';')
+                > name
+        })
+        .unwrap_or(last + 1);
+    lines.insert(index, &new_line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// The module of a day: `dayN` for [`DEFAULT_YEAR`], and `yearYYYY::dayN` for other years.
+fn module_path(year: u32, day: u32) -> String {
+    match year {
+        DEFAULT_YEAR => format!("day{day}"),
+        year => format!("year{year}::day{day}"),
+    }
+}
+
+/// Add `crate::dayN::solution(),` (or `crate::yearYYYY::dayN::solution(),`) to the list in
+/// `registry.rs`, after the earlier days.
+fn add_registration(registry: &str, year: u32, day: u32) -> Result<String> {
+    let new_line = format!("            crate::{}::solution(),", module_path(year, day));
+    let mut lines = registry.lines().collect::<Vec<_>>();
+
+    let registered_day = |line: &str| -> Option<(u32, u32)> {
+        let path = line
+            .trim()
+            .strip_prefix("crate::")?
+            .strip_suffix("::solution(),")?;
+        let (year, day) = match path.split_once("::") {
+            Some((year, day)) => (year.strip_prefix("year")?.parse().ok()?, day),
+            None => (DEFAULT_YEAR, path),
+        };
+        Some((year, day.strip_prefix("day")?.parse().ok()?))
+    };
+    let last_lower = lines
+        .iter()
+        .rposition(|l| registered_day(l).is_some_and(|d| d < (year, day)));
+    let index = match last_lower {
+        Some(index) => index + 1,
+        None => lines
+            .iter()
+            .position(|l| registered_day(l).is_some())
+            .ok_or_else(|| anyhow!("no registered days in registry.rs"))?,
+    };
+    lines.insert(index, &new_line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// The `mod.rs` of a year other than [`DEFAULT_YEAR`], before its first day is added.
+const YEAR_MODULE_TEMPLATE: &str = "//! The days of YEAR.\n\n";
+
+/// Create the module of a day from the template, wire it into the registry, and create an
+/// empty example input with its expected answers. Returns the files that were created or
+/// changed.
+///
+/// Days of [`DEFAULT_YEAR`] go in `src/dayN.rs`, and days of other years in
+/// `src/yearYYYY/dayN.rs`, with `src/yearYYYY/mod.rs` created for the first one.
+///
+/// `root` is the repository root. Refuses to touch anything if the day already exists.
+pub fn new_day(root: &Path, year: u32, day: u32) -> Result<Vec<PathBuf>> {
+    if day == 0 {
+        Err(anyhow!("days start at 1"))?;
+    }
+    let src = match year {
+        DEFAULT_YEAR => root.join("src"),
+        year => root.join(format!("src/year{year}")),
+    };
+    let module_path = src.join(format!("day{day}.rs"));
+    if module_path.exists() {
+        Err(anyhow!("{module_path:?} already exists"))?;
+    }
+
+    let lib_path = root.join("src/lib.rs");
+    let registry_path = root.join("src/registry.rs");
+    let mut lib = read(&lib_path)?;
+    let registry = read(&registry_path)?;
+
+    // The file declaring the day's module, and whether it is new.
+    let (parent_path, parent, new_parent) = match year {
+        DEFAULT_YEAR => (lib_path.clone(), lib.clone(), false),
+        year => {
+            let path = src.join("mod.rs");
+            match path.exists() {
+                true => (path.clone(), read(&path)?, false),
+                false => {
+                    let year_module = format!("year{year}");
+                    if lib.lines().any(|l| l == format!("pub mod {year_module};")) {
+                        Err(anyhow!(
+                            "{year_module} is declared in {lib_path:?} but {path:?} is missing"
+                        ))?;
+                    }
+                    lib = add_module(&lib, &year_module)?;
+                    (
+                        path,
+                        YEAR_MODULE_TEMPLATE.replace("YEAR", &year.to_string()),
+                        true,
+                    )
+                }
+            }
+        }
+    };
+    if parent.lines().any(|l| l == format!("pub mod day{day};")) {
+        Err(anyhow!("day{day} is already declared in {parent_path:?}"))?;
+    }
+    let parent = match new_parent {
+        true => format!("{parent}pub mod day{day};\n"),
+        false => add_module(&parent, &format!("day{day}"))?,
+    };
+
+    let (input_prefix, year_line) = match year {
+        DEFAULT_YEAR => (format!("../input/{year}/"), String::new()),
+        year => (
+            format!("../../input/{year}/"),
+            format!("            .year({year})\n"),
+        ),
+    };
+    let module = read(&root.join(TEMPLATE_PATH))?
+        .replace("../input/YEAR/", &input_prefix)
+        .replace("            .year(YEAR)\n", &year_line)
+        .replace("dayX", &format!("day{day}"))
+        .replace("Day::new(X,", &format!("Day::new({day},"));
+    let registry = add_registration(&registry, year, day)?;
+
+    let mut changed = vec![];
+    if let Some(dir) = module_path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("could not create {dir:?}"))?;
+    }
+    write(&module_path, &module)?;
+    changed.push(module_path);
+    write(&parent_path, &parent)?;
+    changed.push(parent_path);
+    if new_parent {
+        write(&lib_path, &lib)?;
+        changed.push(lib_path);
+    }
+    write(&registry_path, &registry)?;
+    changed.push(registry_path);
+
+    let example_path = root.join(format!("input/{year}/day{day}.example.txt"));
+    let expected_path = example_path.with_extension("expected");
+    for (path, contents) in [(example_path, ""), (expected_path, EXPECTED_TEMPLATE)] {
+        if path.exists() {
+            continue;
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("could not create {dir:?}"))?;
+        }
+        write(&path, contents)?;
+        changed.push(path);
+    }
+
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{add_module, add_registration, new_day};
+    use anyhow::Result;
+    use tempfile::TempDir;
+
+    /// Enough of a repository to scaffold in, independent of the days in the working tree.
+    const FIXTURE: [(&str, &str); 3] = [
+        (
+            "src/dayX.rs",
+            "pub(crate) fn solution() -> Box<dyn Solution> {
+    Box::new(
+        Day::new(X, parse_data)
+            .year(YEAR)
+            .part1(|d| part1(d)),
+    )
+}
+
+mod test {
+    const EXAMPLE_INPUT: &str = include_str!(\"../input/YEAR/dayX.example.txt\");
+
+    #[test]
+    #[ignore = \"not implemented yet\"]
+    fn part1_example() {}
+}
+",
+        ),
+        (
+            "src/lib.rs",
+            "pub mod day1;\npub mod day11;\npub mod day2;\npub mod submit;\n\npub use registry::registry;\n",
+        ),
+        (
+            "src/registry.rs",
+            "fn all() -> Vec<Box<dyn Solution>> {
+    vec![
+            crate::day1::solution(),
+            crate::day2::solution(),
+            crate::day11::solution(),
+    ]
+}
+",
+        ),
+    ];
+
+    fn fixture() -> Result<TempDir> {
+        let root = tempfile::tempdir()?;
+        std::fs::create_dir(root.path().join("src"))?;
+        for (file, contents) in FIXTURE {
+            std::fs::write(root.path().join(file), contents)?;
+        }
+        Ok(root)
+    }
+
+    #[test]
+    fn module_is_inserted_sorted() -> Result<()> {
+        let lib = "use x;\n\npub mod day1;\npub mod day10;\npub mod day2;\npub mod registry;\n";
+        assert_eq!(
+            add_module(lib, "day12")?,
+            "use x;\n\npub mod day1;\npub mod day10;\npub mod day12;\npub mod day2;\npub mod registry;\n"
+        );
+        assert_eq!(
+            add_module(lib, "day3")?,
+            "use x;\n\npub mod day1;\npub mod day10;\npub mod day2;\npub mod day3;\npub mod registry;\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn registration_follows_lower_days() -> Result<()> {
+        let registry = "let x = vec![\n            crate::day2::solution(),\n            crate::day4::solution(),\n];\n";
+        assert_eq!(
+            add_registration(registry, 2022, 3)?,
+            "let x = vec![\n            crate::day2::solution(),\n            crate::day3::solution(),\n            crate::day4::solution(),\n];\n"
+        );
+        assert_eq!(
+            add_registration(registry, 2022, 1)?,
+            "let x = vec![\n            crate::day1::solution(),\n            crate::day2::solution(),\n            crate::day4::solution(),\n];\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn other_years_follow_the_default_year() -> Result<()> {
+        let registry = "let x = vec![\n            crate::day25::solution(),\n            crate::year2023::day2::solution(),\n];\n";
+        assert_eq!(
+            add_registration(registry, 2023, 1)?,
+            "let x = vec![\n            crate::day25::solution(),\n            crate::year2023::day1::solution(),\n            crate::year2023::day2::solution(),\n];\n"
+        );
+        assert_eq!(
+            add_registration(registry, 2022, 3)?,
+            "let x = vec![\n            crate::day3::solution(),\n            crate::day25::solution(),\n            crate::year2023::day2::solution(),\n];\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scaffolds_a_day_once() -> Result<()> {
+        let root = fixture()?;
+
+        let changed = new_day(root.path(), 2022, 12)?;
+        assert_eq!(changed.len(), 5);
+
+        let module = std::fs::read_to_string(root.path().join("src/day12.rs"))?;
+        assert!(module.contains("Day::new(12, parse_data)"));
+        assert!(module.contains("include_str!(\"../input/2022/day12.example.txt\")"));
+        assert!(module.contains("#[ignore = \"not implemented yet\"]"));
+        assert!(!module.contains("dayX") && !module.contains(".year("));
+
+        let lib = std::fs::read_to_string(root.path().join("src/lib.rs"))?;
+        assert!(lib.contains("pub mod day11;\npub mod day12;\npub mod day2;"));
+        let registry = std::fs::read_to_string(root.path().join("src/registry.rs"))?;
+        assert!(
+            registry.contains("crate::day11::solution(),\n            crate::day12::solution(),")
+        );
+        assert!(root.path().join("input/2022/day12.example.txt").exists());
+        assert!(root
+            .path()
+            .join("input/2022/day12.example.expected")
+            .exists());
+
+        assert!(new_day(root.path(), 2022, 12).is_err());
+        assert!(new_day(root.path(), 2022, 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn scaffolds_another_year() -> Result<()> {
+        let root = fixture()?;
+
+        let changed = new_day(root.path(), 2023, 5)?;
+        assert_eq!(changed.len(), 6);
+        let module = std::fs::read_to_string(root.path().join("src/year2023/day5.rs"))?;
+        assert!(module.contains("Day::new(5, parse_data)\n            .year(2023)\n"));
+        assert!(module.contains("include_str!(\"../../input/2023/day5.example.txt\")"));
+        let lib = std::fs::read_to_string(root.path().join("src/lib.rs"))?;
+        assert!(lib.contains("pub mod submit;\npub mod year2023;\n"));
+        let registry = std::fs::read_to_string(root.path().join("src/registry.rs"))?;
+        assert!(registry
+            .contains("crate::day11::solution(),\n            crate::year2023::day5::solution(),"));
+
+        // The year's module exists now, so only the new day is added to it.
+        let changed = new_day(root.path(), 2023, 1)?;
+        assert_eq!(changed.len(), 5);
+        let year = std::fs::read_to_string(root.path().join("src/year2023/mod.rs"))?;
+        assert!(year.ends_with("pub mod day1;\npub mod day5;\n"));
+        assert!(root
+            .path()
+            .join("input/2023/day1.example.expected")
+            .exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,377 @@
+use std::{any::Any, fmt::Display, io::BufRead};
+
+use anyhow::{anyhow, Result};
+use tracing::{debug, debug_span, info_span};
+
+use crate::{normalize::Normalize, parse::ParseError, stream::Stream, DEFAULT_YEAR};
+
+/// Which half of a puzzle a solver answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.number().fmt(f)
+    }
+}
+
+impl std::str::FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(anyhow!("bad part {s:?} (expected 1 or 2)")),
+        }
+    }
+}
+
+/// A solver for one part, optionally with a variant name (like day6's `A_Tuples`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Variant {
+    pub part: Part,
+    pub name: Option<&'static str>,
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "part{} ({name})", self.part),
+            None => write!(f, "part{}", self.part),
+        }
+    }
+}
+
+/// The answer of a part, independent of the type the solver returns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+    /// A monochrome picture, row by row.
+    Bitmap {
+        width: usize,
+        pixels: Vec<bool>,
+    },
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(n) => n.fmt(f),
+            Answer::Text(s) => s.fmt(f),
+            Answer::Bitmap { width, pixels } => {
+                for (index, row) in pixels.chunks((*width).max(1)).enumerate() {
+                    if index > 0 {
+                        writeln!(f)?;
+                    }
+                    let row = row
+                        .iter()
+                        .map(|&x| if x { '#' } else { '.' })
+                        .collect::<String>();
+                    write!(f, "{row}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Counts too big for an `i64` are kept exactly, as text.
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        i64::try_from(value)
+            .map(Answer::Number)
+            .unwrap_or_else(|_| Answer::Text(value.to_string()))
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::Number(value.into())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+/// The answer of an implementation in a cross-check, `None` if it can't handle the input.
+pub type CheckedAnswer = Result<Option<Answer>>;
+
+/// The output of a generator, as produced by [`Solution::parse`].
+pub struct Parsed(Box<dyn Any + Send + Sync>);
+
+/// Object-safe access to the generator and solvers of one day.
+pub trait Solution: Send + Sync {
+    fn year(&self) -> u32;
+
+    fn day(&self) -> u32;
+
+    /// All registered solvers, in registration order.
+    fn variants(&self) -> Vec<Variant>;
+
+    fn parse(&self, input: &str) -> Result<Parsed>;
+
+    fn solve(&self, data: &Parsed, variant: Variant) -> Result<Answer>;
+
+    /// The parts that can also be solved with [`Solution::solve_stream`].
+    fn stream_parts(&self) -> Vec<Part>;
+
+    /// Solve `part` while reading `input`, without holding all of it in memory. Gives the
+    /// same answer as parsing the whole input and solving that.
+    fn solve_stream(&self, input: &mut dyn BufRead, part: Part) -> Result<Answer>;
+
+    /// Names of the other sets of implementations that must agree, beside the variants of
+    /// a part. See [`crate::differential`].
+    fn cross_checks(&self) -> Vec<&'static str>;
+
+    /// The answer of every implementation in a cross-check.
+    fn run_cross_check(
+        &self,
+        data: &Parsed,
+        name: &str,
+    ) -> Result<Vec<(&'static str, CheckedAnswer)>>;
+
+    /// The first registered solver for `part`.
+    fn default_variant(&self, part: Part) -> Option<Variant> {
+        self.variants().into_iter().find(|v| v.part == part)
+    }
+
+    /// Find a solver by part and (case insensitive) variant name.
+    fn find_variant(&self, part: Part, name: Option<&str>) -> Result<Variant> {
+        let Some(name) = name else {
+            return self
+                .default_variant(part)
+                .ok_or_else(|| anyhow!("day {} has no part {part}", self.day()));
+        };
+        self.variants()
+            .into_iter()
+            .find(|v| v.part == part && v.name.is_some_and(|n| n.eq_ignore_ascii_case(name)))
+            .ok_or_else(|| anyhow!("day {} part {part} has no variant {name:?}", self.day()))
+    }
+
+    fn run(&self, input: &str, part: Part) -> Result<Answer> {
+        let variant = self.find_variant(part, None)?;
+        self.solve(&self.parse(input)?, variant)
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        self.run(input, Part::One)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        self.run(input, Part::Two)
+    }
+}
+
+type Generator<T> = fn(&str) -> Result<T, ParseError>;
+type Solver<T> = Box<dyn Fn(&T) -> Result<Answer> + Send + Sync>;
+type StreamSolver = Box<dyn Fn(Stream<'_>) -> Result<Answer> + Send + Sync>;
+type CheckedFn<T, R> = fn(&T) -> Result<Option<R>>;
+type CheckedSolver<T> = Box<dyn Fn(&T) -> CheckedAnswer + Send + Sync>;
+type CrossCheck<T> = (&'static str, Vec<(&'static str, CheckedSolver<T>)>);
+
+/// A [`Solution`] built from a generator function and its solvers.
+pub(crate) struct Day<T> {
+    year: u32,
+    day: u32,
+    normalize: Normalize,
+    generator: Generator<T>,
+    solvers: Vec<(Variant, Solver<T>)>,
+    streams: Vec<(Part, StreamSolver)>,
+    cross_checks: Vec<CrossCheck<T>>,
+}
+
+impl<T: Send + Sync + 'static> Day<T> {
+    pub(crate) fn new(day: u32, generator: Generator<T>) -> Self {
+        Self {
+            year: DEFAULT_YEAR,
+            day,
+            normalize: Normalize::RAW,
+            generator,
+            solvers: vec![],
+            streams: vec![],
+            cross_checks: vec![],
+        }
+    }
+
+    /// Clean up the input before the generator sees it, instead of passing it as read.
+    pub(crate) fn normalize(mut self, normalize: Normalize) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// For days of any year but [`DEFAULT_YEAR`].
+    #[allow(dead_code)] // Until `new-day --year` adds the first day of another year.
+    pub(crate) fn year(mut self, year: u32) -> Self {
+        self.year = year;
+        self
+    }
+
+    pub(crate) fn part1<R: Into<Answer> + 'static>(self, f: fn(&T) -> Result<R>) -> Self {
+        self.solver(Part::One, None, f)
+    }
+
+    pub(crate) fn part2<R: Into<Answer> + 'static>(self, f: fn(&T) -> Result<R>) -> Self {
+        self.solver(Part::Two, None, f)
+    }
+
+    pub(crate) fn solver<R: Into<Answer> + 'static>(
+        mut self,
+        part: Part,
+        name: Option<&'static str>,
+        f: fn(&T) -> Result<R>,
+    ) -> Self {
+        let solver = Box::new(move |data: &T| f(data).map(Into::into));
+        self.solvers.push((Variant { part, name }, solver));
+        self
+    }
+
+    /// Also solve `part` from a stream, which is cleaned up like the input of the generator.
+    pub(crate) fn stream<R: Into<Answer> + 'static>(
+        mut self,
+        part: Part,
+        f: fn(Stream<'_>) -> Result<R>,
+    ) -> Self {
+        let solver = Box::new(move |stream: Stream<'_>| f(stream).map(Into::into));
+        self.streams.push((part, solver));
+        self
+    }
+
+    /// Implementations that must agree on every input, like a part's variants, but that
+    /// don't answer a part. They return `None` for input they can't handle.
+    pub(crate) fn cross_check<R: Into<Answer> + 'static>(
+        mut self,
+        name: &'static str,
+        implementations: &[(&'static str, CheckedFn<T, R>)],
+    ) -> Self {
+        let implementations = implementations
+            .iter()
+            .map(|&(name, f)| {
+                let solver: CheckedSolver<T> =
+                    Box::new(move |data: &T| Ok(f(data)?.map(Into::into)));
+                (name, solver)
+            })
+            .collect();
+        self.cross_checks.push((name, implementations));
+        self
+    }
+}
+
+impl<T: Send + Sync + 'static> Solution for Day<T> {
+    fn year(&self) -> u32 {
+        self.year
+    }
+
+    fn day(&self) -> u32 {
+        self.day
+    }
+
+    fn variants(&self) -> Vec<Variant> {
+        self.solvers.iter().map(|(v, _)| *v).collect()
+    }
+
+    fn parse(&self, input: &str) -> Result<Parsed> {
+        let _span = debug_span!("generator", day = self.day).entered();
+        debug!(bytes = input.len(), "parsing input");
+        let input = self.normalize.apply(input);
+        let data = (self.generator)(&input).map_err(|e| {
+            debug!(error = %e, "input rejected");
+            e.with_day(self.day)
+        })?;
+        Ok(Parsed(Box::new(data)))
+    }
+
+    fn solve(&self, data: &Parsed, variant: Variant) -> Result<Answer> {
+        let data = data
+            .0
+            .downcast_ref::<T>()
+            .ok_or_else(|| anyhow!("data was not parsed by day {}", self.day))?;
+        let (_, solver) = self
+            .solvers
+            .iter()
+            .find(|(v, _)| *v == variant)
+            .ok_or_else(|| anyhow!("day {} has no {variant}", self.day))?;
+        let _span = info_span!("solver", day = self.day, %variant).entered();
+        let answer = solver(data)?;
+        debug!(%answer, "solved");
+        Ok(answer)
+    }
+
+    fn cross_checks(&self) -> Vec<&'static str> {
+        self.cross_checks.iter().map(|(name, _)| *name).collect()
+    }
+
+    fn run_cross_check(
+        &self,
+        data: &Parsed,
+        name: &str,
+    ) -> Result<Vec<(&'static str, CheckedAnswer)>> {
+        let data = data
+            .0
+            .downcast_ref::<T>()
+            .ok_or_else(|| anyhow!("data was not parsed by day {}", self.day))?;
+        let (_, implementations) = self
+            .cross_checks
+            .iter()
+            .find(|(n, _)| *n == name)
+            .ok_or_else(|| anyhow!("day {} has no cross-check {name:?}", self.day))?;
+        let _span = info_span!("cross-check", day = self.day, name).entered();
+        Ok(implementations
+            .iter()
+            .map(|(name, f)| (*name, f(data)))
+            .collect())
+    }
+
+    fn stream_parts(&self) -> Vec<Part> {
+        self.streams.iter().map(|(p, _)| *p).collect()
+    }
+
+    fn solve_stream(&self, input: &mut dyn BufRead, part: Part) -> Result<Answer> {
+        let (_, solver) = self
+            .streams
+            .iter()
+            .find(|(p, _)| *p == part)
+            .ok_or_else(|| anyhow!("day {} part {part} can't be streamed", self.day))?;
+        let _span = info_span!("solver", day = self.day, %part, streamed = true).entered();
+        let answer = solver(Stream::new(input, self.normalize)).map_err(|e| {
+            match e.downcast::<ParseError>() {
+                Ok(error) => error.with_day(self.day).into(),
+                Err(e) => e,
+            }
+        })?;
+        debug!(%answer, "solved");
+        Ok(answer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Answer;
+
+    #[test]
+    fn big_counts_are_not_wrapped() {
+        assert_eq!(Answer::from(42usize), Answer::Number(42));
+        assert_eq!(
+            Answer::from(usize::MAX),
+            Answer::Text(usize::MAX.to_string())
+        );
+    }
+}
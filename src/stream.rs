@@ -0,0 +1,217 @@
+//! Reading input bit by bit from any [`BufRead`], for inputs too big to hold in memory.
+//!
+//! The input is cleaned up the same way as a whole input would be by [`Normalize`], so a
+//! streaming solver sees exactly what its in-memory generator sees.
+
+use std::{
+    collections::VecDeque,
+    io::{BufRead, Bytes, Read},
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::normalize::{Normalize, Whitespace};
+
+/// The input of a streaming solver.
+pub struct Stream<'a> {
+    reader: &'a mut dyn BufRead,
+    normalize: Normalize,
+}
+
+impl<'a> Stream<'a> {
+    pub fn new(reader: &'a mut dyn BufRead, normalize: Normalize) -> Self {
+        Self { reader, normalize }
+    }
+
+    /// Call `f` with every line and its 0-based index, stopping at the first error.
+    ///
+    /// Lines are only held back while they are empty, in case they are trailing empty lines
+    /// that [`Normalize::drop_trailing_empty_lines`] removes.
+    pub fn for_each_line(self, mut f: impl FnMut(usize, &str) -> Result<()>) -> Result<()> {
+        let mut raw = String::new();
+        let mut line = String::new();
+        let mut held = vec![];
+        for index in 0.. {
+            raw.clear();
+            if self.reader.read_line(&mut raw)? == 0 {
+                break;
+            }
+            let mut text = raw.strip_suffix('\n').unwrap_or(&raw);
+            if index == 0 && self.normalize.line_endings {
+                text = text.strip_prefix('\u{feff}').unwrap_or(text);
+            }
+            line.clear();
+            self.normalize.push_line(&mut line, text);
+
+            if self.normalize.drop_trailing_empty_lines && line.trim().is_empty() {
+                held.push((index, line.clone()));
+                continue;
+            }
+            for (index, line) in held.drain(..) {
+                f(index, &line)?;
+            }
+            f(index, &line)?;
+        }
+        Ok(())
+    }
+
+    /// The characters of the first line, for inputs of a single line like day 6's.
+    ///
+    /// Like [`Stream::for_each_line`], but without holding the line in memory. Tabs are not
+    /// expanded.
+    pub fn chars(self) -> Chars<'a> {
+        Chars {
+            bytes: self.reader.bytes(),
+            normalize: self.normalize,
+            first: true,
+            text_seen: false,
+            spaces: String::new(),
+            ready: VecDeque::new(),
+            done: false,
+        }
+    }
+}
+
+/// See [`Stream::chars`].
+pub struct Chars<'a> {
+    bytes: Bytes<&'a mut dyn BufRead>,
+    normalize: Normalize,
+    first: bool,
+    /// Whether anything but whitespace was read, so leading whitespace is behind us.
+    text_seen: bool,
+    /// Whitespace that is only passed on if more text follows.
+    spaces: String,
+    ready: VecDeque<char>,
+    done: bool,
+}
+
+impl Chars<'_> {
+    fn byte(&mut self) -> Result<Option<u8>> {
+        Ok(self.bytes.next().transpose()?)
+    }
+
+    fn decode(&mut self) -> Result<Option<char>> {
+        let Some(first) = self.byte()? else {
+            return Ok(None);
+        };
+        let mut buf = [first, 0, 0, 0];
+        let len = match first.leading_ones() {
+            0 => 1,
+            n @ 2..=4 => n as usize,
+            _ => Err(anyhow!("input is not valid UTF-8"))?,
+        };
+        for slot in &mut buf[1..len] {
+            *slot = self
+                .byte()?
+                .ok_or_else(|| anyhow!("input is not valid UTF-8"))?;
+        }
+        let text = std::str::from_utf8(&buf[..len])?;
+        Ok(text.chars().next())
+    }
+
+    /// The end of the line: trailing whitespace is only kept when the line is kept as it is.
+    fn end(&mut self) {
+        self.done = true;
+        if self.normalize.whitespace == Whitespace::Keep {
+            let mut spaces = std::mem::take(&mut self.spaces);
+            if self.normalize.line_endings && spaces.ends_with('\r') {
+                spaces.pop();
+            }
+            self.ready.extend(spaces.chars());
+        }
+    }
+}
+
+impl Iterator for Chars<'_> {
+    type Item = Result<char>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(c) = self.ready.pop_front() {
+                return Some(Ok(c));
+            }
+            if self.done {
+                return None;
+            }
+            let c = match self.decode() {
+                Ok(Some(c)) => c,
+                Ok(None) => {
+                    self.end();
+                    continue;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+            let first = std::mem::replace(&mut self.first, false);
+
+            match c {
+                '\u{feff}' if first && self.normalize.line_endings => {}
+                '\n' => self.end(),
+                c if c.is_whitespace() => {
+                    if self.text_seen || self.normalize.whitespace != Whitespace::Trim {
+                        self.spaces.push(c);
+                    }
+                }
+                c => {
+                    self.text_seen = true;
+                    self.ready.extend(self.spaces.drain(..));
+                    self.ready.push_back(c);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Stream;
+    use crate::normalize::Normalize;
+    use anyhow::Result;
+
+    fn lines(input: &str, normalize: Normalize) -> Result<Vec<(usize, String)>> {
+        let mut reader = input.as_bytes();
+        let mut lines = vec![];
+        Stream::new(&mut reader, normalize).for_each_line(|index, line| {
+            lines.push((index, line.to_string()));
+            Ok(())
+        })?;
+        Ok(lines)
+    }
+
+    #[test]
+    fn lines_are_normalized() -> Result<()> {
+        for input in [
+            "\u{feff} 1\r\n\r\n2 \r\n \r\n\r\n",
+            "\n1\n\n2",
+            "a\tb\n  c  \n\n",
+        ] {
+            for normalize in [Normalize::TRIM, Normalize::INDENTED, Normalize::RAW] {
+                let whole = normalize.apply(input);
+                let expected = whole
+                    .split_inclusive('\n')
+                    .map(|l| l.strip_suffix('\n').unwrap_or(l).to_string())
+                    .enumerate()
+                    .collect::<Vec<_>>();
+                assert_eq!(lines(input, normalize)?, expected, "{input:?}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn chars_of_the_first_line() -> Result<()> {
+        let chars = |mut input: &[u8], normalize| -> Result<String> {
+            Stream::new(&mut input, normalize).chars().collect()
+        };
+        let trim = Normalize::TRIM;
+        assert_eq!(chars("\u{feff} ab c \r\nd\n".as_bytes(), trim)?, "ab c");
+        assert_eq!(chars(b" ab c \r\nd\n", Normalize::INDENTED)?, " ab c");
+        assert_eq!(chars("äb c \r\n".as_bytes(), Normalize::RAW)?, "äb c \r");
+        assert_eq!(chars(b"", trim)?, "");
+        assert!(chars(b"a\xff", trim).is_err());
+        assert!(chars(b"a\xc3", trim).is_err());
+        Ok(())
+    }
+}
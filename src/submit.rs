@@ -0,0 +1,560 @@
+use std::{
+    fmt::Display,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::{
+    client::Client,
+    solution::{Answer, Part},
+    DEFAULT_YEAR,
+};
+
+/// How long the site makes you wait after a wrong answer, at least.
+const WRONG_ANSWER_DELAY: Duration = Duration::from_secs(60);
+
+/// What the site said about a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after the previous attempt.
+    RateLimited {
+        wait: Option<Duration>,
+    },
+    /// The part is already solved (or the previous part isn't yet).
+    WrongLevel,
+    Unrecognized,
+}
+
+impl Outcome {
+    /// Interpret the HTML page returned after posting an answer.
+    pub fn from_response(page: &str) -> Self {
+        let text = main_text(page);
+        if text.contains("That's the right answer") {
+            Outcome::Correct
+        } else if text.contains("That's not the right answer") {
+            if text.contains("your answer is too high") {
+                Outcome::TooHigh
+            } else if text.contains("your answer is too low") {
+                Outcome::TooLow
+            } else {
+                Outcome::Wrong
+            }
+        } else if text.contains("You gave an answer too recently") {
+            Outcome::RateLimited {
+                wait: parse_wait(&text),
+            }
+        } else if text.contains("You don't seem to be solving the right level") {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unrecognized
+        }
+    }
+
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Outcome::Wrong | Outcome::TooHigh | Outcome::TooLow)
+    }
+}
+
+/// The contents of the `<main>` element with tags removed, or the whole page if there is none.
+fn main_text(page: &str) -> String {
+    let main = page
+        .split_once("<main>")
+        .and_then(|(_, rest)| rest.split_once("</main>"))
+        .map_or(page, |(main, _)| main);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in main.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Find the `You have 1m 5s left to wait` part of a rate limit message.
+fn parse_wait(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("You have ")?;
+    let (amount, _) = rest.split_once(" left to wait")?;
+    let mut secs = 0;
+    for item in amount.split_whitespace() {
+        let (value, factor) = if let Some(m) = item.strip_suffix('m') {
+            (m, 60)
+        } else if let Some(s) = item.strip_suffix('s') {
+            (s, 1)
+        } else {
+            return None;
+        };
+        secs += value.parse::<u64>().ok()? * factor;
+    }
+    Some(Duration::from_secs(secs))
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::TooHigh => write!(f, "too-high"),
+            Outcome::TooLow => write!(f, "too-low"),
+            Outcome::RateLimited { wait: Some(wait) } => {
+                write!(f, "rate-limited:{}", wait.as_secs())
+            }
+            Outcome::RateLimited { wait: None } => write!(f, "rate-limited"),
+            Outcome::WrongLevel => write!(f, "wrong-level"),
+            Outcome::Unrecognized => write!(f, "unrecognized"),
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "correct" => Outcome::Correct,
+            "wrong" => Outcome::Wrong,
+            "too-high" => Outcome::TooHigh,
+            "too-low" => Outcome::TooLow,
+            "rate-limited" => Outcome::RateLimited { wait: None },
+            "wrong-level" => Outcome::WrongLevel,
+            "unrecognized" => Outcome::Unrecognized,
+            _ => {
+                let secs = s
+                    .strip_prefix("rate-limited:")
+                    .and_then(|secs| secs.parse().ok())
+                    .ok_or_else(|| anyhow!("bad outcome {s:?}"))?;
+                Outcome::RateLimited {
+                    wait: Some(Duration::from_secs(secs)),
+                }
+            }
+        })
+    }
+}
+
+/// One line of the submission log.
+///
+/// The day is written as `YEAR/DAY`, or just `DAY` for [`DEFAULT_YEAR`], as it was before
+/// there were other years.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub time: SystemTime,
+    pub year: u32,
+    pub day: u32,
+    pub part: Part,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+impl Display for Attempt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let secs = self
+            .time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let day = match self.year {
+            DEFAULT_YEAR => self.day.to_string(),
+            year => format!("{year}/{}", self.day),
+        };
+        write!(
+            f,
+            "{secs}\t{day}\t{}\t{}\t{}",
+            self.part, self.outcome, self.answer
+        )
+    }
+}
+
+impl FromStr for Attempt {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.splitn(5, '\t');
+        let mut next = |name| fields.next().ok_or_else(|| anyhow!("missing {name}"));
+        let secs: u64 = next("time")?.parse()?;
+        let day = next("day")?;
+        let (year, day) = match day.split_once('/') {
+            Some((year, day)) => (year.parse()?, day.parse()?),
+            None => (DEFAULT_YEAR, day.parse()?),
+        };
+        let part = next("part")?.parse()?;
+        let outcome = next("outcome")?.parse()?;
+        let answer = next("answer")?.to_string();
+        Ok(Self {
+            time: UNIX_EPOCH + Duration::from_secs(secs),
+            year,
+            day,
+            part,
+            answer,
+            outcome,
+        })
+    }
+}
+
+/// Every answer ever submitted, one tab separated [`Attempt`] per line.
+#[derive(Debug)]
+pub struct SubmissionLog {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl SubmissionLog {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let attempts = match std::fs::read_to_string(&path) {
+            Ok(text) => text
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(index, line)| {
+                    line.parse()
+                        .with_context(|| format!("{path:?} line {}", index + 1))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(e) => Err(e).with_context(|| format!("could not read {path:?}"))?,
+        };
+        Ok(Self { path, attempts })
+    }
+
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    pub fn record(&mut self, attempt: Attempt) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("could not create {dir:?}"))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("could not open {:?}", self.path))?;
+        writeln!(file, "{attempt}")?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+
+    fn attempts_for(&self, year: u32, day: u32, part: Part) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |a| a.year == year && a.day == day && a.part == part)
+    }
+
+    /// Why `answer` is already known to be wrong, if it is.
+    pub fn known_wrong(&self, year: u32, day: u32, part: Part, answer: &str) -> Option<String> {
+        let value = answer.parse::<i64>().ok();
+        self.attempts_for(year, day, part).find_map(|a| {
+            let bound = a.answer.parse::<i64>().ok();
+            match (&a.outcome, value, bound) {
+                (outcome, _, _) if outcome.is_wrong() && a.answer == answer => {
+                    Some(format!("{answer} was already submitted and is {outcome}"))
+                }
+                (Outcome::TooHigh, Some(v), Some(b)) if v >= b => {
+                    Some(format!("{answer} is not below {b}, which was too high"))
+                }
+                (Outcome::TooLow, Some(v), Some(b)) if v <= b => {
+                    Some(format!("{answer} is not above {b}, which was too low"))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// The earliest time the site will accept another answer for `day` of `year`, judging by
+    /// the log.
+    pub fn next_allowed(&self, year: u32, day: u32) -> Option<SystemTime> {
+        let last = self
+            .attempts
+            .iter()
+            .rev()
+            .find(|a| a.year == year && a.day == day)?;
+        match last.outcome {
+            Outcome::RateLimited { wait: Some(wait) } => Some(last.time + wait),
+            ref outcome if outcome.is_wrong() => Some(last.time + WRONG_ANSWER_DELAY),
+            _ => None,
+        }
+    }
+}
+
+/// What happened when trying to submit an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Submission {
+    /// Posted to the site.
+    Submitted(Outcome),
+    /// The part is already solved, so the manifest decided instead.
+    Known { correct: bool, expected: String },
+}
+
+/// Submit answers for one year, keeping the log and answer manifest up to date.
+pub struct Submitter<'a> {
+    pub client: &'a Client,
+    pub year: u32,
+    pub log: SubmissionLog,
+    pub manifest_path: &'a Path,
+}
+
+impl Submitter<'_> {
+    pub fn submit(&mut self, day: u32, part: Part, answer: &Answer) -> Result<Submission> {
+        if matches!(answer, Answer::Bitmap { .. }) {
+            Err(anyhow!(
+                "the answer is a picture; submit the letters it shows instead"
+            ))?;
+        }
+        self.send(day, part, answer)
+    }
+
+    /// Submit an answer typed in by hand, kept as text in the manifest if it's right.
+    pub fn submit_text(&mut self, day: u32, part: Part, answer: &str) -> Result<Submission> {
+        self.send(day, part, &Answer::Text(answer.trim().to_string()))
+    }
+
+    fn send(&mut self, day: u32, part: Part, submitted: &Answer) -> Result<Submission> {
+        let answer = submitted.to_string();
+        let answer = answer.as_str();
+        if answer.is_empty() || answer.contains('\n') {
+            Err(anyhow!("bad answer {answer:?}"))?;
+        }
+
+        let mut manifest = crate::manifest::Manifest::load_or_default(self.manifest_path)?;
+        if let Some(expected) = manifest.get(day, part) {
+            return Ok(Submission::Known {
+                correct: expected == answer,
+                expected: expected.to_string(),
+            });
+        }
+
+        if let Some(reason) = self.log.known_wrong(self.year, day, part, answer) {
+            Err(anyhow!("not submitting: {reason}"))?;
+        }
+        if let Some(next) = self.log.next_allowed(self.year, day) {
+            if let Ok(wait) = next.duration_since(SystemTime::now()) {
+                Err(anyhow!(
+                    "not submitting: wait {}s more before answering day {day} again",
+                    wait.as_secs() + 1
+                ))?;
+            }
+        }
+
+        let page = self.client.post_form(
+            &format!("/{}/day/{day}/answer", self.year),
+            &[("level", &part.to_string()), ("answer", answer)],
+        )?;
+        let outcome = Outcome::from_response(&page);
+
+        self.log.record(Attempt {
+            time: SystemTime::now(),
+            year: self.year,
+            day,
+            part,
+            answer: answer.to_string(),
+            outcome: outcome.clone(),
+        })?;
+
+        if outcome == Outcome::Correct {
+            manifest.set(day, part, submitted);
+            manifest.save(self.manifest_path)?;
+        }
+
+        Ok(Submission::Submitted(outcome))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Attempt, Outcome, Submission, SubmissionLog, Submitter};
+    use crate::{
+        client::{
+            test::{serve, test_config},
+            Client,
+        },
+        manifest::Manifest,
+        solution::Part,
+    };
+    use anyhow::Result;
+    use std::time::{Duration, SystemTime};
+
+    fn page(message: &str) -> String {
+        format!("<html><body><main>\n<article><p>{message}</p></article>\n</main></body></html>")
+    }
+
+    #[test]
+    fn parse_responses() {
+        let cases = [
+            ("That's the right answer!  You are <em>one gold star</em> closer.", Outcome::Correct),
+            ("That's not the right answer.  If you're stuck, ...", Outcome::Wrong),
+            ("That's not the right answer; your answer is too high.", Outcome::TooHigh),
+            ("That's not the right answer; your answer is too low.", Outcome::TooLow),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.",
+                Outcome::RateLimited { wait: Some(Duration::from_secs(65)) },
+            ),
+            ("You don't seem to be solving the right level.  Did you already complete it?", Outcome::WrongLevel),
+            ("Something else", Outcome::Unrecognized),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(
+                Outcome::from_response(&page(message)),
+                expected,
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn attempt_round_trip() -> Result<()> {
+        let mut attempt = Attempt {
+            time: SystemTime::UNIX_EPOCH + Duration::from_secs(1670000000),
+            year: 2022,
+            day: 5,
+            part: Part::Two,
+            answer: "CMZ".to_string(),
+            outcome: Outcome::RateLimited {
+                wait: Some(Duration::from_secs(30)),
+            },
+        };
+        assert_eq!(
+            attempt.to_string(),
+            "1670000000\t5\t2\trate-limited:30\tCMZ"
+        );
+        assert_eq!(attempt.to_string().parse::<Attempt>()?, attempt);
+
+        attempt.year = 2023;
+        assert_eq!(
+            attempt.to_string(),
+            "1670000000\t2023/5\t2\trate-limited:30\tCMZ"
+        );
+        assert_eq!(attempt.to_string().parse::<Attempt>()?, attempt);
+        Ok(())
+    }
+
+    #[test]
+    fn refuses_known_wrong_answers() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut log = SubmissionLog::open(dir.path().join("log"))?;
+        let long_ago = SystemTime::UNIX_EPOCH;
+        for (answer, outcome) in [
+            ("100", Outcome::TooHigh),
+            ("10", Outcome::TooLow),
+            ("50", Outcome::Wrong),
+        ] {
+            log.record(Attempt {
+                time: long_ago,
+                year: 2022,
+                day: 1,
+                part: Part::One,
+                answer: answer.to_string(),
+                outcome,
+            })?;
+        }
+
+        let log = SubmissionLog::open(dir.path().join("log"))?;
+        assert_eq!(log.attempts().len(), 3);
+        assert!(log.known_wrong(2022, 1, Part::One, "100").is_some());
+        assert!(log.known_wrong(2022, 1, Part::One, "150").is_some());
+        assert!(log.known_wrong(2022, 1, Part::One, "5").is_some());
+        assert!(log.known_wrong(2022, 1, Part::One, "50").is_some());
+        assert!(log.known_wrong(2022, 1, Part::One, "51").is_none());
+        assert!(log.known_wrong(2022, 1, Part::Two, "100").is_none());
+        assert!(log.known_wrong(2023, 1, Part::One, "100").is_none());
+        assert!(log.next_allowed(2022, 1).unwrap() < SystemTime::now());
+        assert!(log.next_allowed(2023, 1).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn submit_records_and_saves_correct_answer() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let manifest_path = dir.path().join("answers.toml");
+        std::fs::write(&manifest_path, "")?;
+
+        let (base_url, server) = serve(vec![
+            (
+                200,
+                "<main><p>That's not the right answer; your answer is too low.</p></main>",
+            ),
+            (200, "<main><p>That's the right answer!</p></main>"),
+        ]);
+        let client = Client::new(test_config(base_url, dir.path()));
+        let mut submitter = Submitter {
+            client: &client,
+            year: 2022,
+            log: SubmissionLog::open(dir.path().join("submissions.log"))?,
+            manifest_path: &manifest_path,
+        };
+
+        assert_eq!(
+            submitter.submit_text(3, Part::Two, "41")?,
+            Submission::Submitted(Outcome::TooLow)
+        );
+        // Throttled locally, without contacting the server.
+        assert!(submitter.submit_text(3, Part::Two, "42").is_err());
+
+        // Pretend the wrong answer was long ago.
+        let log_path = dir.path().join("submissions.log");
+        let text = std::fs::read_to_string(&log_path)?;
+        let (_, rest) = text.split_once('\t').unwrap();
+        std::fs::write(&log_path, format!("0\t{rest}"))?;
+        submitter.log = SubmissionLog::open(&log_path)?;
+
+        assert!(submitter.submit_text(3, Part::Two, "40").is_err());
+        assert_eq!(
+            submitter.submit_text(3, Part::Two, "42")?,
+            Submission::Submitted(Outcome::Correct)
+        );
+
+        // Solved parts are answered from the manifest.
+        assert_eq!(
+            Manifest::load(&manifest_path)?.get(3, Part::Two),
+            Some("42")
+        );
+        assert_eq!(
+            submitter.submit_text(3, Part::Two, "43")?,
+            Submission::Known {
+                correct: false,
+                expected: "42".to_string()
+            }
+        );
+
+        let seen = server.join().unwrap();
+        assert_eq!(seen[0].request_line, "POST /2022/day/3/answer HTTP/1.1");
+        assert_eq!(seen[0].body, "level=2&answer=41");
+        assert_eq!(submitter.log.attempts().len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn submit_without_manifest() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let manifest_path = dir.path().join("answers.toml");
+
+        let (base_url, server) = serve(vec![(200, "<main><p>That's the right answer!</p></main>")]);
+        let client = Client::new(test_config(base_url, dir.path()));
+        let mut submitter = Submitter {
+            client: &client,
+            year: 2023,
+            log: SubmissionLog::open(dir.path().join("submissions.log"))?,
+            manifest_path: &manifest_path,
+        };
+
+        assert_eq!(
+            submitter.submit_text(1, Part::One, "7")?,
+            Submission::Submitted(Outcome::Correct)
+        );
+        assert_eq!(Manifest::load(&manifest_path)?.get(1, Part::One), Some("7"));
+        server.join().unwrap();
+        Ok(())
+    }
+}
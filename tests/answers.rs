@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use aoc_2022::{
+    manifest::{default_manifest_path, Check, Manifest},
+    registry,
+    runner::{default_input_path, read_input, run_days_parallel},
+};
+
+#[test]
+#[cfg_attr(
+    not(feature = "verify-answers"),
+    ignore = "runs every day on the real input (use feature 'verify-answers' to enable)"
+)]
+fn real_inputs_match_manifest() -> Result<()> {
+    let manifests = registry()
+        .years()
+        .map(|year| Ok((year, Manifest::load(default_manifest_path(year))?)))
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    let inputs = registry()
+        .iter()
+        .map(|solution| read_input(&default_input_path(solution.year(), solution.day())))
+        .collect::<Result<Vec<_>>>()?;
+    let days = registry()
+        .iter()
+        .zip(&inputs)
+        .map(|(solution, input)| (solution, input.as_str()))
+        .collect::<Vec<_>>();
+
+    let mut failures = vec![];
+    for run in run_days_parallel(&days, |_| true) {
+        let (year, day) = (run.year, run.day);
+        let manifest = &manifests[&year];
+        let parts = match run.parts {
+            Ok(parts) => parts,
+            Err(e) => {
+                failures.push(format!("{year} day {day}: generator failed: {e}"));
+                continue;
+            }
+        };
+        for part in parts {
+            let variant = part.variant;
+            match part.answer {
+                Ok(answer) => match manifest.check(day, variant.part, &answer) {
+                    Check::Correct => {}
+                    Check::Unknown => {
+                        failures.push(format!("{year} day {day} {variant}: no known answer"))
+                    }
+                    Check::Wrong { expected } => failures.push(format!(
+                        "{year} day {day} {variant}: expected\n{expected}\nbut got\n{answer}"
+                    )),
+                },
+                Err(e) => failures.push(format!("{year} day {day} {variant}: failed: {e}")),
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("{}", failures.join("\n")))
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::{anyhow, Result};
+use aoc_2022::{
+    examples::discover,
+    manifest::Check,
+    registry,
+    runner::{input_dir, read_input, run_day},
+};
+
+#[test]
+fn examples_match_expected_answers() -> Result<()> {
+    let mut failures = vec![];
+    let mut examples = vec![];
+    for year in registry().years() {
+        examples.extend(discover(input_dir(year))?.into_iter().map(|e| (year, e)));
+    }
+    for (year, example) in examples {
+        let day = example.day;
+        let label = format!("{year} day {day} {}", example.name);
+        let Some(solution) = registry().get_in(year, day) else {
+            failures.push(format!("{label}: day is not implemented"));
+            continue;
+        };
+        let checked = |part| {
+            example.expected.contains_key(&part)
+                && (cfg!(feature = "long-running-tests") || !example.long_running.contains(&part))
+        };
+        if !solution.variants().iter().any(|v| checked(v.part)) {
+            continue;
+        }
+
+        let input = read_input(&example.input_path)?;
+        let run = run_day(solution, &input, |v| checked(v.part));
+        let parts = match run.parts {
+            Ok(parts) => parts,
+            Err(e) => {
+                failures.push(format!("{label}: generator failed: {e}"));
+                continue;
+            }
+        };
+        for part in parts {
+            let variant = part.variant;
+            match part.answer {
+                Ok(answer) => match example.check(variant.part, &answer) {
+                    Check::Correct | Check::Unknown => {}
+                    Check::Wrong { expected } => failures.push(format!(
+                        "{label} {variant}: expected\n{expected}\nbut got\n{answer}"
+                    )),
+                },
+                Err(e) => failures.push(format!("{label} {variant}: failed: {e}")),
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("{}", failures.join("\n")))
+    }
+}
+
+/// Files saved on Windows, with a byte order mark, `\r\n` and an extra empty line at the
+/// end, give the same answers.
+#[test]
+fn windows_line_endings_give_the_same_answers() -> Result<()> {
+    let mut failures = vec![];
+    for year in registry().years() {
+        for example in discover(input_dir(year))? {
+            let label = format!("{year} day {} {}", example.day, example.name);
+            let Some(solution) = registry().get_in(year, example.day) else {
+                continue;
+            };
+            let checked = |part| {
+                example.expected.contains_key(&part)
+                    && (cfg!(feature = "long-running-tests")
+                        || !example.long_running.contains(&part))
+            };
+            if !solution.variants().iter().any(|v| checked(v.part)) {
+                continue;
+            }
+
+            let input = read_input(&example.input_path)?;
+            let windows = format!("\u{feff}{}\r\n", input.replace('\n', "\r\n"));
+            let answers = |input: &str| match run_day(solution, input, |v| checked(v.part)).parts {
+                Ok(parts) => Ok(parts
+                    .into_iter()
+                    .map(|p| p.answer.map_err(|e| e.to_string()))
+                    .collect::<Vec<_>>()),
+                Err(e) => Err(e.to_string()),
+            };
+            if answers(&input) != answers(&windows) {
+                failures.push(format!("{label}: answers differ with windows line endings"));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("{}", failures.join("\n")))
+    }
+}
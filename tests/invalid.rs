@@ -0,0 +1,121 @@
+//! Every broken input in `input/YEAR/invalid/` is rejected with the error pinned in its
+//! sidecar file, `dayN.name.errors`:
+//!
+//! ```toml
+//! part1 = "rucksack 2: no item is in both compartments"
+//! part2 = "group 1: no item is in all three rucksacks"
+//! ```
+//!
+//! A part the sidecar leaves out has to solve the input without an error.
+//!
+//! Nothing catches a panic, so a solver that still panics on one of them fails the run.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context, Result};
+use aoc_2022::{
+    registry,
+    runner::{input_dir, read_input},
+    Part,
+};
+
+/// Split `day3.odd-rucksack.txt` into `(3, "odd-rucksack")`.
+fn parse_file_name(name: &str) -> Option<(u32, &str)> {
+    let (day, rest) = name.strip_prefix("day")?.split_once('.')?;
+    Some((day.parse().ok()?, rest.strip_suffix(".txt")?))
+}
+
+fn expected_errors(text: &str) -> Result<BTreeMap<Part, String>> {
+    let table: toml::Table = text.parse()?;
+    let mut expected = BTreeMap::new();
+    for (key, value) in table {
+        let part = key
+            .strip_prefix("part")
+            .ok_or_else(|| anyhow!("expected keys like `part1`, found `{key}`"))?
+            .parse()?;
+        let toml::Value::String(error) = value else {
+            Err(anyhow!("{key} should be the text of an error"))?
+        };
+        expected.insert(part, error);
+    }
+    Ok(expected)
+}
+
+#[test]
+fn invalid_inputs_are_reported() -> Result<()> {
+    let mut failures = vec![];
+    let mut checked = 0;
+    for year in registry().years() {
+        let dir = input_dir(year).join("invalid");
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut paths = entries
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<_>>>()?;
+        paths.sort();
+
+        for path in paths {
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            let Some((day, case)) = parse_file_name(name) else {
+                continue;
+            };
+            let label = format!("{year} day {day} {case}");
+            let solution = registry()
+                .get_in(year, day)
+                .ok_or_else(|| anyhow!("{label}: day is not implemented"))?;
+            let errors_path = path.with_extension("errors");
+            let expected = std::fs::read_to_string(&errors_path)
+                .map_err(anyhow::Error::from)
+                .and_then(|text| expected_errors(&text))
+                .with_context(|| format!("{label}: bad {}", errors_path.display()))?;
+            if expected.is_empty() {
+                Err(anyhow!(
+                    "{label}: {} lists no errors",
+                    errors_path.display()
+                ))?;
+            }
+
+            let input = read_input(&path)?;
+            let parsed = solution.parse(&input);
+            for variant in solution.variants() {
+                let outcome = match &parsed {
+                    Ok(data) => solution.solve(data, variant),
+                    Err(e) => Err(anyhow!("{e:#}")),
+                };
+                let expected = expected.get(&variant.part).map(String::as_str);
+                match (outcome, expected) {
+                    (Err(e), Some(expected)) if format!("{e:#}") == expected => {}
+                    (Ok(_), None) => {}
+                    (Err(e), _) => failures.push(format!("{label} {variant}: failed with {e:#}")),
+                    (Ok(answer), Some(_)) => {
+                        failures.push(format!("{label} {variant}: succeeded with {answer}"))
+                    }
+                }
+            }
+            for part in solution.stream_parts() {
+                let outcome = solution.solve_stream(&mut input.as_bytes(), part);
+                let expected = expected.get(&part).map(String::as_str);
+                match outcome {
+                    Err(e) if Some(format!("{e:#}").as_str()) == expected => {}
+                    Ok(_) if expected.is_none() => {}
+                    outcome => failures.push(format!(
+                        "{label} part{part} streamed: {:?}",
+                        outcome.map_err(|e| format!("{e:#}"))
+                    )),
+                }
+            }
+            checked += 1;
+        }
+    }
+
+    assert!(checked > 0, "no invalid inputs were found");
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("{}", failures.join("\n")))
+    }
+}